@@ -2,6 +2,9 @@ use pyo3::prelude::*;
 use pyo3::wrap_pyfunction;
 use pyo3::types::{PyDict, PyList};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 /// Python bindings for VectraEdge
 #[pymodule]
@@ -26,11 +29,25 @@ pub struct VectorIndex {
     column_name: String,
 }
 
+/// Process-wide per-topic change feed shared by every `StreamSubscription`
+/// in this process, playing the role the real VectraEdge server's topic
+/// log (`crate::streaming::StreamManager` in the main crate) will play once
+/// this client talks to it over the wire instead of in-process.
+fn topic_log() -> &'static Mutex<HashMap<String, Vec<Value>>> {
+    static TOPICS: OnceLock<Mutex<HashMap<String, Vec<Value>>>> = OnceLock::new();
+    TOPICS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
 #[pyclass]
 pub struct StreamSubscription {
     id: String,
     topic: String,
     status: String,
+    /// Next unread offset into the topic log - the causal cursor handed
+    /// back by `poll` and advanced by `ack`. Only advancing on `ack` (not
+    /// on every `poll`) means a dropped response can be re-polled without
+    /// losing or duplicating messages.
+    cursor: Mutex<u64>,
 }
 
 #[pymethods]
@@ -72,10 +89,13 @@ impl VectraClient {
     }
     
     fn subscribe_stream(&self, topic: &str) -> PyResult<StreamSubscription> {
+        topic_log().lock().unwrap().entry(topic.to_string()).or_insert_with(Vec::new);
+
         Ok(StreamSubscription {
-            id: format!("sub_{}", topic.len()),
+            id: uuid::Uuid::new_v4().to_string(),
             topic: topic.to_string(),
             status: "active".to_string(),
+            cursor: Mutex::new(0),
         })
     }
     
@@ -85,7 +105,13 @@ impl VectraClient {
     }
     
     fn insert_data(&self, table: &str, data: &PyDict) -> PyResult<()> {
-        println!("Inserting data into table '{}': {:?}", table, data);
+        let row = py_to_json(data)?;
+        topic_log()
+            .lock()
+            .unwrap()
+            .entry(table.to_string())
+            .or_insert_with(Vec::new)
+            .push(row);
         Ok(())
     }
     
@@ -132,7 +158,16 @@ impl VectraClient {
 #[pymethods]
 impl VectorIndex {
     fn insert_vector(&self, id: u32, vector: Vec<f32>) -> PyResult<()> {
-        println!("Inserting vector {} into index {}.{}", id, self.table_name, self.column_name);
+        topic_log()
+            .lock()
+            .unwrap()
+            .entry(self.table_name.clone())
+            .or_insert_with(Vec::new)
+            .push(serde_json::json!({
+                "id": id,
+                "column": self.column_name,
+                "vector": vector,
+            }));
         Ok(())
     }
     
@@ -162,21 +197,125 @@ impl StreamSubscription {
     fn get_id(&self) -> PyResult<&str> {
         Ok(&self.id)
     }
-    
+
     fn get_topic(&self) -> PyResult<&str> {
         Ok(&self.topic)
     }
-    
+
     fn get_status(&self) -> PyResult<&str> {
         Ok(&self.status)
     }
-    
+
+    /// Block (releasing the GIL) until new items land on this subscription's
+    /// topic or `timeout_ms` elapses, returning `{"items": [...], "cursor": n}`.
+    /// Pass the returned cursor to `ack` once the batch has been processed;
+    /// the next `poll` only returns items past the last acked cursor, so a
+    /// reconnect neither skips nor re-delivers anything already acked.
+    fn poll(&self, py: Python, timeout_ms: u64) -> PyResult<PyObject> {
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        let topic = self.topic.clone();
+
+        loop {
+            let cursor = *self.cursor.lock().unwrap();
+            let batch = {
+                let log = topic_log().lock().unwrap();
+                log.get(&topic)
+                    .map(|messages| messages.iter().skip(cursor as usize).cloned().collect::<Vec<_>>())
+                    .unwrap_or_default()
+            };
+
+            if !batch.is_empty() || Instant::now() >= deadline {
+                let next_cursor = cursor + batch.len() as u64;
+                let items = PyList::new(py, batch.iter().map(|v| json_to_py(py, v)).collect::<Vec<_>>());
+
+                let result = PyDict::new(py);
+                result.set_item("items", items)?;
+                result.set_item("cursor", next_cursor)?;
+                return Ok(result.into());
+            }
+
+            py.allow_threads(|| std::thread::sleep(Duration::from_millis(20)));
+        }
+    }
+
+    /// Record (in this subscription's in-memory cursor) that everything up
+    /// to `cursor` has been processed, so
+    /// the next `poll` resumes from there instead of redelivering it.
+    fn ack(&self, cursor: u64) -> PyResult<()> {
+        let mut current = self.cursor.lock().unwrap();
+        *current = (*current).max(cursor);
+        Ok(())
+    }
+
     fn unsubscribe(&self) -> PyResult<()> {
         println!("Unsubscribing from topic: {}", self.topic);
         Ok(())
     }
 }
 
+/// Converts a Python dict passed to `insert_data` into the `serde_json::Value`
+/// stored in `topic_log`, so `StreamSubscription::poll` can hand subscribers
+/// real inserted rows instead of never seeing any.
+fn py_to_json(dict: &PyDict) -> PyResult<Value> {
+    Python::with_gil(|py| {
+        let mut map = serde_json::Map::new();
+        for (key, value) in dict.iter() {
+            let key = key.extract::<String>()?;
+            map.insert(key, py_any_to_json(py, value)?);
+        }
+        Ok(Value::Object(map))
+    })
+}
+
+fn py_any_to_json(py: Python, value: &PyAny) -> PyResult<Value> {
+    if value.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(b) = value.extract::<bool>() {
+        Ok(Value::Bool(b))
+    } else if let Ok(i) = value.extract::<i64>() {
+        Ok(Value::Number(i.into()))
+    } else if let Ok(f) = value.extract::<f64>() {
+        Ok(serde_json::Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null))
+    } else if let Ok(s) = value.extract::<String>() {
+        Ok(Value::String(s))
+    } else if let Ok(list) = value.downcast::<PyList>() {
+        let items = list
+            .iter()
+            .map(|item| py_any_to_json(py, item))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(Value::Array(items))
+    } else if let Ok(dict) = value.downcast::<PyDict>() {
+        py_to_json(dict)
+    } else {
+        Ok(Value::String(value.str()?.to_string()))
+    }
+}
+
+fn json_to_py(py: Python, value: &Value) -> PyObject {
+    match value {
+        Value::Null => py.None(),
+        Value::Bool(b) => b.to_object(py),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.to_object(py)
+            } else {
+                n.as_f64().unwrap_or(0.0).to_object(py)
+            }
+        }
+        Value::String(s) => s.to_object(py),
+        Value::Array(items) => {
+            PyList::new(py, items.iter().map(|v| json_to_py(py, v)).collect::<Vec<_>>()).to_object(py)
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, v) in map {
+                let _ = dict.set_item(key, json_to_py(py, v));
+            }
+            dict.to_object(py)
+        }
+    }
+}
+
 #[pyfunction]
 fn health_check() -> PyResult<PyObject> {
     Python::with_gil(|py| {
@@ -195,14 +334,14 @@ fn version() -> PyResult<&str> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[test]
     fn test_client_creation() {
         let client = VectraClient::new(Some("localhost"), Some(9000));
         assert_eq!(client.host, "localhost");
         assert_eq!(client.port, 9000);
     }
-    
+
     #[test]
     fn test_vector_index_creation() {
         let index = VectorIndex {
@@ -212,4 +351,53 @@ mod tests {
         assert_eq!(index.table_name, "test_table");
         assert_eq!(index.column_name, "test_column");
     }
+
+    #[test]
+    fn test_stream_subscription_cursor_only_advances_on_ack() {
+        let topic = "test_topic_cursor";
+        topic_log().lock().unwrap().insert(topic.to_string(), vec![serde_json::json!({"id": 1})]);
+
+        let subscription = StreamSubscription {
+            id: "sub-1".to_string(),
+            topic: topic.to_string(),
+            status: "active".to_string(),
+            cursor: Mutex::new(0),
+        };
+
+        // Before any ack, the cursor is still 0 even though items exist.
+        assert_eq!(*subscription.cursor.lock().unwrap(), 0);
+
+        subscription.ack(1).unwrap();
+        assert_eq!(*subscription.cursor.lock().unwrap(), 1);
+
+        // Acking an older cursor never moves it backwards.
+        subscription.ack(0).unwrap();
+        assert_eq!(*subscription.cursor.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_insert_data_publishes_to_topic_log() {
+        Python::with_gil(|py| {
+            let client = VectraClient::new(None, None);
+            let data = PyDict::new(py);
+            data.set_item("id", 1).unwrap();
+            data.set_item("title", "hello").unwrap();
+
+            client.insert_data("inserted_rows", data).unwrap();
+
+            let log = topic_log().lock().unwrap();
+            let rows = log.get("inserted_rows").unwrap();
+            assert_eq!(rows.len(), 1);
+            assert_eq!(rows[0]["id"], serde_json::json!(1));
+            assert_eq!(rows[0]["title"], serde_json::json!("hello"));
+        });
+    }
+
+    #[test]
+    fn test_subscribe_stream_starts_topic_log() {
+        let client = VectraClient::new(None, None);
+        let subscription = client.subscribe_stream("orders").unwrap();
+        assert_eq!(subscription.topic, "orders");
+        assert!(topic_log().lock().unwrap().contains_key("orders"));
+    }
 }
@@ -1,15 +1,53 @@
+use std::ops::Range;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use anyhow::Result;
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, RwLock};
+use anyhow::{anyhow, Result};
 use serde_json::Value;
 use std::collections::HashMap;
 
+use crate::cache::{Cache, CacheConfig, EvictionPolicy};
 use crate::config::Config;
+use crate::embedding::{self, EmbeddingProvider};
+use crate::queue::EmbeddingQueue;
+use crate::splitter::{SplitterParams, TextSplitter};
+use crate::storage::StorageManager;
+
+/// Consecutive failed probes a model tolerates before `ModelHealth::status`
+/// drops from `Degraded` to `Failed`. One failure is treated as a transient
+/// blip; this many in a row means the provider is actually down.
+const HEALTH_FAILURE_THRESHOLD: u32 = 3;
+
+/// Table `generate_embedding` persists computed embeddings to, so a warm
+/// cache survives a process restart - see `embedding_cache_key`.
+const EMBEDDING_CACHE_TABLE: &str = "embedding_cache";
+
+/// Hashes `model` and `text` together so entries for different models never
+/// collide (and switching the active embedding model can't return a vector
+/// computed by a different one), matching the hashing style
+/// `crate::cache::Cache::shard_index` already uses for routing keys.
+fn embedding_cache_key(model: &str, text: &str) -> String {
+    format!("{:016x}", seahash::hash(format!("{}\u{0}{}", model, text).as_bytes()))
+}
 
 pub struct AIRuntime {
     config: Config,
     models: Arc<RwLock<HashMap<String, AIModel>>>,
-    embedding_cache: Arc<RwLock<HashMap<String, Vec<f32>>>>,
+    /// One `EmbeddingQueue` per embedding model name, each wrapping the
+    /// `EmbeddingProvider` built by `crate::embedding::build_provider` when
+    /// that model is registered via `add_model`.
+    embedding_queues: Arc<RwLock<HashMap<String, Arc<EmbeddingQueue>>>>,
+    /// Name of the embedding model `generate_embedding` dispatches to.
+    active_embedding_model: Arc<RwLock<String>>,
+    /// LRU-bounded in-memory tier, keyed by `embedding_cache_key`. Backed by
+    /// `storage` as a second, persistent tier - see `generate_embedding`.
+    embedding_cache: Arc<Cache<Vec<f32>>>,
+    storage: Arc<StorageManager>,
+    /// Live health of every registered model, kept up to date by a
+    /// background watcher (embedding models) or set once at registration
+    /// (models with no real provider to probe). `generate_embedding`/
+    /// `generate_text` watch these before dispatching - see `await_ready`.
+    model_health: Arc<RwLock<HashMap<String, watch::Receiver<ModelHealth>>>>,
 }
 
 pub struct AIModel {
@@ -27,102 +65,286 @@ pub enum ModelType {
     Custom,
 }
 
+/// Lifecycle of a registered model, as tracked by `AIRuntime::model_health`.
+/// `Loading` is the initial state before any probe has completed; `Ready`
+/// and `Degraded` both still serve requests (a single failed probe isn't
+/// treated as an outage), while `Failed` means `HEALTH_FAILURE_THRESHOLD`
+/// consecutive probes have failed and the model should not be dispatched to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelStatus {
+    Loading,
+    Ready,
+    Degraded,
+    Failed,
+}
+
+impl std::fmt::Display for ModelStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            ModelStatus::Loading => "loading",
+            ModelStatus::Ready => "ready",
+            ModelStatus::Degraded => "degraded",
+            ModelStatus::Failed => "failed",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelHealth {
+    pub status: ModelStatus,
+    /// Round-trip latency of the most recently completed probe, if any.
+    pub last_probe_latency_ms: Option<u64>,
+}
+
+impl Default for ModelHealth {
+    fn default() -> Self {
+        Self { status: ModelStatus::Loading, last_probe_latency_ms: None }
+    }
+}
+
+impl ModelHealth {
+    fn ready(latency_ms: u64) -> Self {
+        Self { status: ModelStatus::Ready, last_probe_latency_ms: Some(latency_ms) }
+    }
+
+    fn unready(consecutive_failures: u32, latency_ms: u64) -> Self {
+        let status = if consecutive_failures >= HEALTH_FAILURE_THRESHOLD {
+            ModelStatus::Failed
+        } else {
+            ModelStatus::Degraded
+        };
+        Self { status, last_probe_latency_ms: Some(latency_ms) }
+    }
+}
+
+/// Spawns a background task that probes `provider` with a lightweight
+/// `embed` call on `probe_interval_ms`, publishing the result through the
+/// returned `watch::Receiver`. The task exits once every receiver (including
+/// the one returned here, if the caller drops it) is gone.
+fn spawn_health_watcher(
+    name: String,
+    provider: Arc<dyn EmbeddingProvider>,
+    probe_interval_ms: u64,
+) -> watch::Receiver<ModelHealth> {
+    let (tx, rx) = watch::channel(ModelHealth::default());
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_millis(probe_interval_ms.max(1)));
+        let mut consecutive_failures = 0u32;
+
+        loop {
+            ticker.tick().await;
+
+            let started = Instant::now();
+            let probe = provider.embed(&["ping".to_string()]).await;
+            let latency_ms = started.elapsed().as_millis() as u64;
+
+            let health = match probe {
+                Ok(_) => {
+                    consecutive_failures = 0;
+                    ModelHealth::ready(latency_ms)
+                }
+                Err(err) => {
+                    consecutive_failures += 1;
+                    tracing::warn!("health probe for embedding model '{}' failed: {}", name, err);
+                    ModelHealth::unready(consecutive_failures, latency_ms)
+                }
+            };
+
+            if tx.send(health).is_err() {
+                break;
+            }
+        }
+    });
+
+    rx
+}
+
 impl AIRuntime {
-    pub async fn new(config: &Config) -> Result<Self> {
-        let mut runtime = Self {
-            config,
+    pub async fn new(config: &Config, storage: Arc<StorageManager>) -> Result<Self> {
+        let cache_config = CacheConfig {
+            max_entries: config.ai.embedding_cache_max_entries,
+            max_memory_mb: config.ai.embedding_cache_max_memory_mb,
+            eviction_policy: EvictionPolicy::LRU,
+            ..CacheConfig::default()
+        };
+
+        let runtime = Self {
+            config: config.clone(),
             models: Arc::new(RwLock::new(HashMap::new())),
-            embedding_cache: Arc::new(RwLock::new(HashMap::new())),
+            embedding_queues: Arc::new(RwLock::new(HashMap::new())),
+            active_embedding_model: Arc::new(RwLock::new(config.ai.embedding_model.clone())),
+            embedding_cache: Arc::new(Cache::new(cache_config)),
+            storage,
+            model_health: Arc::new(RwLock::new(HashMap::new())),
         };
-        
+
         // Initialize default models
         runtime.initialize_default_models().await?;
-        
+
         Ok(runtime)
     }
-    
-    async fn initialize_default_models(&mut self) -> Result<()> {
-        let mut models = self.models.write().await;
-        
-        // Add default embedding model
-        models.insert("text-embedding-ada-002".to_string(), AIModel {
-            name: "text-embedding-ada-002".to_string(),
-            model_type: ModelType::Embedding,
-            status: "active".to_string(),
-            parameters: HashMap::new(),
-        });
-        
+
+    async fn initialize_default_models(&self) -> Result<()> {
+        // Add default embedding model, backed by whichever provider
+        // `Config::ai` selects.
+        self.add_model(
+            &self.config.ai.embedding_model.clone(),
+            ModelType::Embedding,
+            HashMap::new(),
+        )
+        .await?;
+
         // Add default text generation model
-        models.insert("llama2".to_string(), AIModel {
-            name: "llama2".to_string(),
-            model_type: ModelType::TextGeneration,
-            status: "active".to_string(),
-            parameters: HashMap::new(),
-        });
-        
+        let mut models = self.models.write().await;
+        models.insert(
+            self.config.ai.text_model.clone(),
+            AIModel {
+                name: self.config.ai.text_model.clone(),
+                model_type: ModelType::TextGeneration,
+                status: "active".to_string(),
+                parameters: HashMap::new(),
+            },
+        );
+        drop(models);
+        // No real provider backs text generation yet (see `generate_text`),
+        // so there's nothing to probe - mark it Ready immediately rather
+        // than leaving it stuck in `Loading` forever.
+        self.mark_ready_immediately(&self.config.ai.text_model.clone()).await;
+
+        Ok(())
+    }
+
+    /// Publishes a permanent `Ready` health status for `name` without
+    /// spawning a probe loop - used for models (text generation,
+    /// classification, `ModelType::Custom`) that have no real
+    /// `EmbeddingProvider` to probe.
+    async fn mark_ready_immediately(&self, name: &str) {
+        let (_tx, rx) = watch::channel(ModelHealth::ready(0));
+        self.model_health.write().await.insert(name.to_string(), rx);
+    }
+
+    /// Waits for `name`'s health to leave `Loading`, erroring if it settles
+    /// on `Failed` or the wait exceeds `model_health_ready_timeout_ms`.
+    async fn await_ready(&self, name: &str) -> Result<()> {
+        let mut receiver = self
+            .model_health
+            .read()
+            .await
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("no health status registered for model '{}'", name))?;
+
+        let timeout = Duration::from_millis(self.config.ai.model_health_ready_timeout_ms);
+        let wait_for_probe = receiver.wait_for(|health| health.status != ModelStatus::Loading);
+
+        let health = tokio::time::timeout(timeout, wait_for_probe)
+            .await
+            .map_err(|_| anyhow!("timed out waiting for model '{}' to become ready", name))?
+            .map_err(|_| anyhow!("health watcher for model '{}' stopped unexpectedly", name))?;
+
+        if health.status == ModelStatus::Failed {
+            return Err(anyhow!("model '{}' is marked Failed and is not serving requests", name));
+        }
+
         Ok(())
     }
-    
+
+    /// Feeds `text` into the active embedding model's `EmbeddingQueue`
+    /// rather than calling its provider directly, so concurrent callers
+    /// (e.g. a bulk document insert loop) share batched provider round-trips
+    /// instead of paying for one request each.
+    ///
+    /// Checks the in-memory `embedding_cache` first, then falls back to
+    /// `storage` (promoting a hit there back into RAM) before dispatching to
+    /// the provider, so a warm cache survives a restart. A freshly computed
+    /// embedding is written to both tiers.
     pub async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
-        // Check cache first
-        let cache_key = format!("embedding:{}", text);
-        let cache = self.embedding_cache.read().await;
-        if let Some(cached_embedding) = cache.get(&cache_key) {
-            return Ok(cached_embedding.clone());
-        }
-        drop(cache);
-        
-        // Generate new embedding
-        let embedding = self.generate_embedding_internal(text).await?;
-        
-        // Cache the result
-        let mut cache = self.embedding_cache.write().await;
-        cache.insert(cache_key, embedding.clone());
-        
-        Ok(embedding)
+        let active_model = self.active_embedding_model.read().await.clone();
+        self.generate_embedding_with_model(&active_model, text).await
     }
-    
-    async fn generate_embedding_internal(&self, text: &str) -> Result<Vec<f32>> {
-        // In a real implementation, this would call Ollama or ONNX Runtime
-        // For now, we'll generate a mock embedding
-        
-        let mut embedding = Vec::with_capacity(384);
-        let text_bytes = text.as_bytes();
-        
-        // Generate deterministic "embedding" based on text content
-        for (i, &byte) in text_bytes.iter().enumerate() {
-            let value = (byte as f32 + i as f32) / 255.0;
-            embedding.push(value);
-        }
-        
-        // Pad to 384 dimensions
-        while embedding.len() < 384 {
-            embedding.push(0.0);
-        }
-        
-        // Normalize
-        let magnitude: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if magnitude > 0.0 {
-            for value in &mut embedding {
-                *value /= magnitude;
+
+    /// Same as `generate_embedding`, but dispatches to `model` directly
+    /// instead of reading the shared `active_embedding_model` - for callers
+    /// (e.g. `crate::jobs::JobManager::reembed_changed_rows`) that need a
+    /// specific model for the duration of one call without mutating global
+    /// state other concurrent callers could observe.
+    pub async fn generate_embedding_with_model(&self, model: &str, text: &str) -> Result<Vec<f32>> {
+        let cache_key = embedding_cache_key(model, text);
+
+        if let Some(cached_embedding) = self.embedding_cache.get(&cache_key).await {
+            return Ok(cached_embedding);
+        }
+
+        if let Some(value) = self.storage.get_data(EMBEDDING_CACHE_TABLE, &cache_key).await? {
+            if let Ok(embedding) = serde_json::from_value::<Vec<f32>>(value) {
+                self.embedding_cache.set(cache_key, embedding.clone()).await?;
+                return Ok(embedding);
             }
         }
-        
+
+        self.await_ready(model).await?;
+
+        let queue = self
+            .embedding_queues
+            .read()
+            .await
+            .get(model)
+            .ok_or_else(|| anyhow!("no embedding provider registered for model '{}'", model))?
+            .clone();
+
+        // The queue writes the in-memory cache itself once the batch it
+        // joins flushes, so a concurrent caller for the same text sees the
+        // cached result. Persist it to storage too once it lands.
+        let embedding = queue.submit(text.to_string(), cache_key.clone()).await?;
+        self.storage
+            .insert_data(EMBEDDING_CACHE_TABLE, &cache_key, &serde_json::json!(embedding))
+            .await?;
         Ok(embedding)
     }
-    
-    pub async fn generate_text(&self, prompt: &str, max_tokens: usize) -> Result<String> {
+
+    /// Splits `text` into overlapping chunks (see `crate::splitter`) and
+    /// embeds each one, so a long document is searchable passage-by-passage
+    /// instead of as a single diluted whole-document vector. Each chunk's
+    /// embedding is L2-normalized, so cosine similarity at search time
+    /// reduces to a dot product.
+    pub async fn embed_document(&self, text: &str) -> Result<Vec<(Range<usize>, Vec<f32>)>> {
+        let splitter = TextSplitter::new(SplitterParams {
+            chunk_size: self.config.ai.embedding_chunk_size,
+            chunk_overlap: self.config.ai.embedding_chunk_overlap,
+        });
+
+        let mut chunks = Vec::new();
+        for range in splitter.split(text) {
+            let embedding = self.generate_embedding(&text[range.clone()]).await?;
+            chunks.push((range, normalize(embedding)));
+        }
+
+        Ok(chunks)
+    }
+
+    /// `model`, if given, only labels the mock response - there's no real
+    /// provider behind text generation yet (see the comment in
+    /// `initialize_default_models`), so
+    /// every call still waits on the configured default `text_model`'s
+    /// health rather than a per-call one.
+    pub async fn generate_text(&self, prompt: &str, max_tokens: usize, model: Option<&str>) -> Result<String> {
+        let text_model = self.config.ai.text_model.clone();
+        self.await_ready(&text_model).await?;
+
         // In a real implementation, this would call Ollama
         // For now, return a mock response
-        
-        let response = format!("Generated response to: '{}' (max tokens: {})", prompt, max_tokens);
+
+        let model_name = model.unwrap_or(&text_model);
+        let response = format!("Generated response to: '{}' (model: {}, max tokens: {})", prompt, model_name, max_tokens);
         Ok(response)
     }
-    
+
     pub async fn classify_text(&self, text: &str, categories: &[String]) -> Result<HashMap<String, f32>> {
         // In a real implementation, this would use a classification model
         // For now, return mock probabilities
-        
+
         let mut results = HashMap::new();
         for category in categories {
             // Generate mock probability based on text content
@@ -130,7 +352,7 @@ impl AIRuntime {
             let probability = (hash % 100) as f32 / 100.0;
             results.insert(category.clone(), probability);
         }
-        
+
         // Normalize probabilities
         let total: f32 = results.values().sum();
         if total > 0.0 {
@@ -138,60 +360,179 @@ impl AIRuntime {
                 *value /= total;
             }
         }
-        
+
         Ok(results)
     }
-    
+
+    /// Registers `name` as a model of `model_type`. For `ModelType::Embedding`
+    /// this also builds and stores the `EmbeddingProvider` that
+    /// `generate_embedding` (when `name` is the active model) dispatches to -
+    /// see `crate::embedding::build_provider` for how `parameters` selects
+    /// and configures the provider.
     pub async fn add_model(&self, name: &str, model_type: ModelType, parameters: HashMap<String, Value>) -> Result<()> {
+        if matches!(model_type, ModelType::Embedding) {
+            let provider = embedding::build_provider(&self.config.ai, name, &parameters)?;
+            self.register_embedding_provider(name, provider).await;
+        } else {
+            // No real provider to probe for these model types yet - see
+            // `mark_ready_immediately`.
+            self.mark_ready_immediately(name).await;
+        }
+
         let mut models = self.models.write().await;
-        
+
         let model = AIModel {
             name: name.to_string(),
             model_type,
             status: "active".to_string(),
             parameters,
         };
-        
+
         models.insert(name.to_string(), model);
-        
+
         Ok(())
     }
-    
+
+    /// Registers `provider` directly under `name`, bypassing
+    /// `crate::embedding::build_provider`'s `Config`-driven selection - used
+    /// by `add_model` for the built-in provider kinds, and handy for tests
+    /// or callers that already have a concrete `EmbeddingProvider`. Spawns
+    /// the `EmbeddingQueue` that fronts it, plus a `spawn_health_watcher`
+    /// task that probes it on `model_health_probe_interval_ms`.
+    pub async fn register_embedding_provider(&self, name: &str, provider: Box<dyn EmbeddingProvider>) {
+        let provider: Arc<dyn EmbeddingProvider> = Arc::from(provider);
+        let debounce = Duration::from_millis(self.config.ai.embedding_queue_debounce_ms);
+        let queue = EmbeddingQueue::spawn(provider.clone(), self.embedding_cache.clone(), debounce);
+        self.embedding_queues.write().await.insert(name.to_string(), Arc::new(queue));
+
+        let health = spawn_health_watcher(name.to_string(), provider, self.config.ai.model_health_probe_interval_ms);
+        self.model_health.write().await.insert(name.to_string(), health);
+    }
+
+    /// The embedding model `generate_embedding` currently dispatches to.
+    pub async fn active_embedding_model(&self) -> String {
+        self.active_embedding_model.read().await.clone()
+    }
+
+    /// Switches which registered embedding model `generate_embedding`
+    /// dispatches to. Errors if `name` hasn't been registered via
+    /// `add_model` with `ModelType::Embedding`.
+    pub async fn set_active_embedding_model(&self, name: &str) -> Result<()> {
+        if !self.embedding_queues.read().await.contains_key(name) {
+            return Err(anyhow!("'{}' is not a registered embedding model", name));
+        }
+        *self.active_embedding_model.write().await = name.to_string();
+        Ok(())
+    }
+
     pub async fn remove_model(&self, name: &str) -> Result<()> {
         let mut models = self.models.write().await;
         models.remove(name);
+        self.embedding_queues.write().await.remove(name);
+        self.model_health.write().await.remove(name);
         Ok(())
     }
-    
+
+    /// Current health of `name`, as last published by its probe (embedding
+    /// models) or set at registration (models with no provider to probe).
+    /// `None` if `name` isn't registered.
+    pub async fn model_health(&self, name: &str) -> Option<ModelHealth> {
+        self.model_health.read().await.get(name).map(|rx| rx.borrow().clone())
+    }
+
+    /// Same as `list_models`, but with each model's `status` replaced by its
+    /// live `ModelHealth` (falling back to the model's stored status if, for
+    /// some reason, no health entry exists) so callers see whether a model
+    /// is actually serving rather than the static string set at creation.
     pub async fn list_models(&self) -> Result<Vec<AIModel>> {
         let models = self.models.read().await;
-        Ok(models.values().cloned().collect())
+        let mut result = Vec::with_capacity(models.len());
+
+        for model in models.values().cloned() {
+            let status = match self.model_health(&model.name).await {
+                Some(health) => health.status.to_string(),
+                None => model.status.clone(),
+            };
+            result.push(AIModel { status, ..model });
+        }
+
+        Ok(result)
     }
-    
+
     pub async fn get_model(&self, name: &str) -> Result<Option<AIModel>> {
         let models = self.models.read().await;
-        Ok(models.get(name).cloned())
+        let Some(model) = models.get(name).cloned() else { return Ok(None) };
+        let status = match self.model_health(&model.name).await {
+            Some(health) => health.status.to_string(),
+            None => model.status.clone(),
+        };
+        Ok(Some(AIModel { status, ..model }))
     }
-    
+
+    /// Clears the in-memory tier only - entries already persisted to
+    /// `storage` are left in place and will repopulate the in-memory cache
+    /// on their next hit.
     pub async fn clear_embedding_cache(&self) -> Result<()> {
-        let mut cache = self.embedding_cache.write().await;
-        cache.clear();
+        self.embedding_cache.clear().await;
         Ok(())
     }
-    
+
+    /// Pushes the embedding cache's current stats into `registry` under the
+    /// name `"embedding"`, for the shared `/metrics` endpoint to scrape -
+    /// mirrors `QueryCache`/`VectorCache::export_metrics`.
+    pub async fn export_metrics(&self, registry: &crate::metrics::CacheMetricsRegistry) {
+        registry.record(self.embedding_cache.metrics("embedding").await).await;
+    }
+
     pub async fn get_cache_stats(&self) -> Result<Value> {
-        let cache = self.embedding_cache.read().await;
+        let cache_stats = self.embedding_cache.get_stats().await;
+        let models = self.models.read().await;
+        let embedding_count = models.values().filter(|m| matches!(m.model_type, ModelType::Embedding)).count();
+        let text_generation_count = models.values().filter(|m| matches!(m.model_type, ModelType::TextGeneration)).count();
+        let classification_count = models.values().filter(|m| matches!(m.model_type, ModelType::Classification)).count();
+
+        let model_health = self.model_health.read().await;
+        let mut model_status = serde_json::Map::new();
+        for name in models.keys() {
+            let health = model_health.get(name).map(|rx| rx.borrow().clone()).unwrap_or_default();
+            model_status.insert(
+                name.clone(),
+                serde_json::json!({
+                    "status": health.status.to_string(),
+                    "last_probe_latency_ms": health.last_probe_latency_ms,
+                }),
+            );
+        }
+
         Ok(serde_json::json!({
-            "cached_embeddings": cache.len(),
+            "cached_embeddings": cache_stats.total_entries,
+            "embedding_cache": {
+                "hits": cache_stats.hits,
+                "misses": cache_stats.misses,
+                "evictions": cache_stats.evictions,
+                "hit_rate": cache_stats.hit_rate,
+            },
             "total_models": {
-                "embedding": 1,
-                "text_generation": 1,
-                "classification": 0
-            }
+                "embedding": embedding_count,
+                "text_generation": text_generation_count,
+                "classification": classification_count
+            },
+            "model_status": model_status
         }))
     }
 }
 
+/// Scales `vector` to unit length, leaving it as-is if it's all zeros.
+fn normalize(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in vector.iter_mut() {
+            *value /= norm;
+        }
+    }
+    vector
+}
+
 impl Clone for AIModel {
     fn clone(&self) -> Self {
         Self {
@@ -206,30 +547,255 @@ impl Clone for AIModel {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
+    /// Storage backing the embedding cache's persistence tier - most tests
+    /// don't care about its configuration, so this mirrors
+    /// `streaming::tests::test_manager`'s pattern of building on
+    /// `Config::default()` rather than threading a tempdir through every test.
+    async fn test_storage() -> Arc<StorageManager> {
+        let mut config = Config::default();
+        let temp_dir = tempfile::tempdir().unwrap();
+        config.storage.rocksdb_path = Some(temp_dir.path().join("rocksdb").to_string_lossy().to_string());
+
+        Arc::new(StorageManager::new(&config).await.unwrap())
+    }
+
+    /// Deterministic, network-free stand-in for a real provider, so unit
+    /// tests don't depend on a live Ollama/ONNX/OpenAI endpoint.
+    struct MockEmbeddingProvider;
+
+    #[async_trait::async_trait]
+    impl EmbeddingProvider for MockEmbeddingProvider {
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            Ok(texts.iter().map(|_| vec![0.1; 384]).collect())
+        }
+
+        fn dimensions(&self) -> usize {
+            384
+        }
+
+        fn max_input_tokens(&self) -> usize {
+            8192
+        }
+    }
+
     #[tokio::test]
-    async fn test_generate_embedding() {
+    async fn test_generate_embedding_uses_active_provider_and_caches() {
         let config = Config::default();
-        let runtime = AIRuntime::new(&config).await.unwrap();
-        
+        let runtime = AIRuntime::new(&config, test_storage().await).await.unwrap();
+        runtime
+            .register_embedding_provider(&config.ai.embedding_model, Box::new(MockEmbeddingProvider))
+            .await;
+
         let embedding = runtime.generate_embedding("hello world").await.unwrap();
         assert_eq!(embedding.len(), 384);
-        
+
         // Test caching
         let cached_embedding = runtime.generate_embedding("hello world").await.unwrap();
         assert_eq!(embedding, cached_embedding);
     }
-    
+
+    #[tokio::test]
+    async fn test_add_model_registers_embedding_provider_and_switch_active() {
+        let config = Config::default();
+        let runtime = AIRuntime::new(&config, test_storage().await).await.unwrap();
+
+        let mut parameters = HashMap::new();
+        parameters.insert("provider".to_string(), serde_json::json!("openai"));
+        parameters.insert("api_key".to_string(), serde_json::json!("sk-test"));
+        parameters.insert("dimensions".to_string(), serde_json::json!(1536));
+
+        runtime.add_model("text-embedding-3-small", ModelType::Embedding, parameters).await.unwrap();
+        runtime.set_active_embedding_model("text-embedding-3-small").await.unwrap();
+
+        let models = runtime.list_models().await.unwrap();
+        assert!(models.iter().any(|m| m.name == "text-embedding-3-small"));
+    }
+
+    #[tokio::test]
+    async fn test_set_active_embedding_model_rejects_unregistered_name() {
+        let config = Config::default();
+        let runtime = AIRuntime::new(&config, test_storage().await).await.unwrap();
+
+        let result = runtime.set_active_embedding_model("does-not-exist").await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_embed_document_returns_one_normalized_vector_per_chunk() {
+        let mut config = Config::default();
+        config.ai.embedding_chunk_size = 20;
+        config.ai.embedding_chunk_overlap = 5;
+        let runtime = AIRuntime::new(&config, test_storage().await).await.unwrap();
+        runtime
+            .register_embedding_provider(&config.ai.embedding_model, Box::new(MockEmbeddingProvider))
+            .await;
+
+        let text = "Paragraph one is here.\n\nParagraph two is here.\n\nParagraph three is here.";
+        let chunks = runtime.embed_document(text).await.unwrap();
+
+        assert!(chunks.len() > 1);
+        for (range, embedding) in &chunks {
+            assert!(range.end <= text.len());
+            let norm: f32 = embedding.iter().map(|v| v * v).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 0.001);
+        }
+    }
+
     #[tokio::test]
     async fn test_classify_text() {
         let config = Config::default();
-        let runtime = AIRuntime::new(&config).await.unwrap();
-        
+        let runtime = AIRuntime::new(&config, test_storage().await).await.unwrap();
+
         let categories = vec!["positive".to_string(), "negative".to_string()];
         let results = runtime.classify_text("I love this!", &categories).await.unwrap();
-        
+
         assert_eq!(results.len(), 2);
         let total: f32 = results.values().sum();
         assert!((total - 1.0).abs() < 0.001); // Probabilities should sum to 1
     }
+
+    struct FailingEmbeddingProvider;
+
+    #[async_trait::async_trait]
+    impl EmbeddingProvider for FailingEmbeddingProvider {
+        async fn embed(&self, _texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            Err(anyhow!("provider unreachable"))
+        }
+
+        fn dimensions(&self) -> usize {
+            384
+        }
+
+        fn max_input_tokens(&self) -> usize {
+            8192
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_embedding_reports_ready_status_after_first_probe() {
+        let mut config = Config::default();
+        config.ai.model_health_probe_interval_ms = 5;
+        let runtime = AIRuntime::new(&config, test_storage().await).await.unwrap();
+        runtime
+            .register_embedding_provider(&config.ai.embedding_model, Box::new(MockEmbeddingProvider))
+            .await;
+
+        // `generate_embedding` itself awaits the first probe, so by the time
+        // it returns the model's health must already be `Ready`.
+        runtime.generate_embedding("hello world").await.unwrap();
+
+        let health = runtime.model_health(&config.ai.embedding_model).await.unwrap();
+        assert_eq!(health.status, ModelStatus::Ready);
+        assert!(health.last_probe_latency_ms.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_generate_embedding_errors_once_model_health_is_failed() {
+        let mut config = Config::default();
+        config.ai.model_health_probe_interval_ms = 5;
+        config.ai.model_health_ready_timeout_ms = 2_000;
+        let runtime = AIRuntime::new(&config, test_storage().await).await.unwrap();
+        runtime
+            .register_embedding_provider(&config.ai.embedding_model, Box::new(FailingEmbeddingProvider))
+            .await;
+
+        // Give the watcher enough failed probes (5ms apart) to cross
+        // HEALTH_FAILURE_THRESHOLD and settle on Failed.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let health = runtime.model_health(&config.ai.embedding_model).await.unwrap();
+        assert_eq!(health.status, ModelStatus::Failed);
+
+        let result = runtime.generate_embedding("hello world").await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_model_health_is_none_for_an_unregistered_model() {
+        let config = Config::default();
+        let runtime = AIRuntime::new(&config, test_storage().await).await.unwrap();
+
+        assert!(runtime.model_health("does-not-exist").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_cache_stats_reports_live_model_status() {
+        let mut config = Config::default();
+        config.ai.model_health_probe_interval_ms = 5;
+        let runtime = AIRuntime::new(&config, test_storage().await).await.unwrap();
+        runtime
+            .register_embedding_provider(&config.ai.embedding_model, Box::new(MockEmbeddingProvider))
+            .await;
+
+        runtime.generate_embedding("hello world").await.unwrap();
+
+        let stats = runtime.get_cache_stats().await.unwrap();
+        let status = &stats["model_status"][&config.ai.embedding_model]["status"];
+        assert_eq!(status, "ready");
+    }
+
+    #[tokio::test]
+    async fn test_generate_embedding_persists_through_storage_across_restarts() {
+        let config = Config::default();
+        let storage = test_storage().await;
+
+        let runtime = AIRuntime::new(&config, storage.clone()).await.unwrap();
+        runtime
+            .register_embedding_provider(&config.ai.embedding_model, Box::new(MockEmbeddingProvider))
+            .await;
+        let embedding = runtime.generate_embedding("hello world").await.unwrap();
+
+        // A fresh runtime sharing the same storage (simulating a restart, its
+        // in-memory cache starts empty) must serve the cached value from
+        // storage without the mock provider being registered on it.
+        let restarted = AIRuntime::new(&config, storage).await.unwrap();
+        let cached = restarted.generate_embedding("hello world").await.unwrap();
+        assert_eq!(cached, embedding);
+    }
+
+    #[tokio::test]
+    async fn test_generate_embedding_reports_hit_and_miss_counters() {
+        let config = Config::default();
+        let runtime = AIRuntime::new(&config, test_storage().await).await.unwrap();
+        runtime
+            .register_embedding_provider(&config.ai.embedding_model, Box::new(MockEmbeddingProvider))
+            .await;
+
+        runtime.generate_embedding("hello world").await.unwrap(); // miss, then cached
+        runtime.generate_embedding("hello world").await.unwrap(); // hit
+
+        let stats = runtime.get_cache_stats().await.unwrap();
+        assert_eq!(stats["embedding_cache"]["hits"], 1);
+        assert_eq!(stats["embedding_cache"]["misses"], 1);
+        assert_eq!(stats["embedding_cache"]["hit_rate"], 50.0);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_cache_respects_configured_max_entries() {
+        let mut config = Config::default();
+        config.ai.embedding_cache_max_entries = 10;
+        let runtime = AIRuntime::new(&config, test_storage().await).await.unwrap();
+        runtime
+            .register_embedding_provider(&config.ai.embedding_model, Box::new(MockEmbeddingProvider))
+            .await;
+
+        for i in 0..500 {
+            runtime.generate_embedding(&format!("text-{}", i)).await.unwrap();
+        }
+
+        // Sharding means the bound isn't exact (see crate::cache::Cache),
+        // but it must still be far from the unbounded growth the old
+        // HashMap-backed cache exhibited.
+        let stats = runtime.get_cache_stats().await.unwrap();
+        assert!(stats["cached_embeddings"].as_u64().unwrap() < 500);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_cache_key_varies_by_model() {
+        assert_ne!(
+            embedding_cache_key("model-a", "same text"),
+            embedding_cache_key("model-b", "same text")
+        );
+    }
 }
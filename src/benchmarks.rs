@@ -8,8 +8,28 @@ use crate::{
     storage::StorageManager,
     ai::AIRuntime,
     config::Config,
+    embedding::EmbeddingProvider,
 };
 
+/// Deterministic, network-free stand-in for a real provider, so this
+/// benchmark doesn't depend on a live Ollama/ONNX/OpenAI endpoint.
+struct MockEmbeddingProvider;
+
+#[async_trait::async_trait]
+impl EmbeddingProvider for MockEmbeddingProvider {
+    async fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|_| vec![0.1; 384]).collect())
+    }
+
+    fn dimensions(&self) -> usize {
+        384
+    }
+
+    fn max_input_tokens(&self) -> usize {
+        8192
+    }
+}
+
 pub fn vector_search_benchmark(c: &mut Criterion) {
     let rt = Runtime::new().unwrap();
     
@@ -23,15 +43,18 @@ pub fn vector_search_benchmark(c: &mut Criterion) {
         // Insert test vectors
         for i in 0..1000 {
             let vector: Vec<f32> = (0..384).map(|j| (i + j) as f32 / 1000.0).collect();
-            vector_index.insert_vector("benchmark_table", "embedding", i as u32, &vector).await.unwrap();
+            vector_index
+                .insert_vector("benchmark_table", "embedding", i as u32, &vector, serde_json::Value::Null)
+                .await
+                .unwrap();
         }
-        
+
         // Benchmark search
         c.bench_function("vector_search_1000_vectors", |b| {
             b.iter(|| {
                 let query_vector: Vec<f32> = (0..384).map(|i| i as f32 / 1000.0).collect();
                 rt.block_on(async {
-                    vector_index.search(&query_vector, 10).await.unwrap()
+                    vector_index.search("benchmark_table", "embedding", &query_vector, 10).await.unwrap()
                 });
             });
         });
@@ -49,7 +72,7 @@ pub fn storage_benchmark(c: &mut Criterion) {
         c.bench_function("create_table", |b| {
             b.iter(|| {
                 rt.block_on(async {
-                    storage.create_table("benchmark_table", "id INT, data TEXT").await.unwrap();
+                    storage.create_table("benchmark_table", "id INT, data TEXT", None, None).await.unwrap();
                 });
             });
         });
@@ -84,8 +107,12 @@ pub fn ai_runtime_benchmark(c: &mut Criterion) {
     
     rt.block_on(async {
         let config = Config::default();
-        let ai_runtime = AIRuntime::new(&config).await.unwrap();
-        
+        let storage = Arc::new(StorageManager::new(&config).await.unwrap());
+        let ai_runtime = AIRuntime::new(&config, storage).await.unwrap();
+        ai_runtime
+            .register_embedding_provider(&config.ai.embedding_model, Box::new(MockEmbeddingProvider))
+            .await;
+
         // Benchmark embedding generation
         c.bench_function("generate_embedding", |b| {
             b.iter(|| {
@@ -99,7 +126,7 @@ pub fn ai_runtime_benchmark(c: &mut Criterion) {
         c.bench_function("generate_text", |b| {
             b.iter(|| {
                 rt.block_on(async {
-                    ai_runtime.generate_text("benchmark prompt", 100).await.unwrap();
+                    ai_runtime.generate_text("benchmark prompt", 100, None).await.unwrap();
                 });
             });
         });
@@ -137,7 +164,7 @@ pub fn engine_benchmark(c: &mut Criterion) {
         c.bench_function("engine_vector_search", |b| {
             b.iter(|| {
                 rt.block_on(async {
-                    engine.vector_search("benchmark query", 10).await.unwrap();
+                    engine.vector_search("documents", "embedding", "benchmark query", 10).await.unwrap();
                 });
             });
         });
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+/// Lowercases and splits on non-alphanumeric boundaries - good enough for
+/// keyword matching without pulling in a real tokenizer.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_lowercase())
+        .collect()
+}
+
+/// Ranks `documents` (id, text) against `query` with the Okapi BM25 formula,
+/// returning `(id, score)` pairs sorted by descending score. A document that
+/// shares no term with the query is omitted rather than scored zero.
+pub fn search(query: &str, documents: &[(u32, String)]) -> Vec<(u32, f64)> {
+    if documents.is_empty() {
+        return Vec::new();
+    }
+
+    let query_terms = tokenize(query);
+    if query_terms.is_empty() {
+        return Vec::new();
+    }
+
+    let doc_tokens: Vec<Vec<String>> = documents.iter().map(|(_, text)| tokenize(text)).collect();
+    let doc_count = doc_tokens.len() as f64;
+    let avg_doc_len = doc_tokens.iter().map(|tokens| tokens.len() as f64).sum::<f64>() / doc_count;
+
+    let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+    for term in &query_terms {
+        let df = doc_tokens.iter().filter(|tokens| tokens.iter().any(|t| t == term)).count();
+        doc_freq.insert(term.as_str(), df);
+    }
+
+    let mut scores = Vec::new();
+    for ((id, _), tokens) in documents.iter().zip(doc_tokens.iter()) {
+        let doc_len = tokens.len() as f64;
+        let mut score = 0.0;
+
+        for term in &query_terms {
+            let df = *doc_freq.get(term.as_str()).unwrap_or(&0);
+            if df == 0 {
+                continue;
+            }
+
+            let tf = tokens.iter().filter(|t| *t == term).count() as f64;
+            let idf = ((doc_count - df as f64 + 0.5) / (df as f64 + 0.5) + 1.0).ln();
+            let denom = tf + K1 * (1.0 - B + B * doc_len / avg_doc_len);
+            score += idf * (tf * (K1 + 1.0)) / denom;
+        }
+
+        if score > 0.0 {
+            scores.push((*id, score));
+        }
+    }
+
+    scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranks_exact_term_match_above_unrelated_document() {
+        let documents = vec![
+            (1u32, "the quick brown fox jumps over the lazy dog".to_string()),
+            (2u32, "completely unrelated text about cooking recipes".to_string()),
+        ];
+
+        let results = search("fox", &documents);
+        assert_eq!(results[0].0, 1);
+    }
+
+    #[test]
+    fn test_documents_with_no_matching_terms_are_omitted() {
+        let documents = vec![(1u32, "alpha beta gamma".to_string())];
+        let results = search("delta", &documents);
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_empty_document_set_returns_empty() {
+        let results = search("anything", &[]);
+        assert!(results.is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+
+use crate::storage_backend::StorageBackend;
+
+const ROLLING_WINDOW: usize = 64;
+
+/// Marker wrapper stored in place of a row's raw bytes once it has been
+/// split into content-defined chunks. The sentinel field name is kept
+/// unusual on purpose so it can't be mistaken for a legitimate document.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChunkManifest {
+    #[serde(rename = "__vectra_chunk_manifest__")]
+    pub is_chunked: bool,
+    pub chunks: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkingParams {
+    pub min_chunk_size: usize,
+    pub max_chunk_size: usize,
+    pub target_chunk_size: usize,
+}
+
+/// A simplified Buzhash: a fixed table of per-byte masks folded into a
+/// rotating accumulator over the trailing `ROLLING_WINDOW` bytes. Good enough
+/// to pick reproducible, content-defined boundaries without pulling in a
+/// rolling-hash crate.
+struct RollingHash {
+    table: [u32; 256],
+    window: VecDeque<u8>,
+    hash: u32,
+}
+
+impl RollingHash {
+    fn new() -> Self {
+        let mut table = [0u32; 256];
+        let mut seed: u32 = 0x9e37_79b9;
+        for entry in table.iter_mut() {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            *entry = seed;
+        }
+
+        Self {
+            table,
+            window: VecDeque::with_capacity(ROLLING_WINDOW),
+            hash: 0,
+        }
+    }
+
+    fn roll(&mut self, byte: u8) -> u32 {
+        self.window.push_back(byte);
+        self.hash = self.hash.rotate_left(1) ^ self.table[byte as usize];
+
+        if self.window.len() > ROLLING_WINDOW {
+            let outgoing = self.window.pop_front().unwrap();
+            self.hash ^= self.table[outgoing as usize].rotate_left(ROLLING_WINDOW as u32 % 32);
+        }
+
+        self.hash
+    }
+}
+
+/// Cut `data` into content-defined chunks: a boundary falls wherever the low
+/// `log2(target_chunk_size)` bits of the rolling hash are zero, clamped to
+/// `[min_chunk_size, max_chunk_size]`.
+pub fn cut_chunks(data: &[u8], params: &ChunkingParams) -> Vec<&[u8]> {
+    if data.len() <= params.min_chunk_size {
+        return vec![data];
+    }
+
+    let boundary_mask = params.target_chunk_size.next_power_of_two().trailing_zeros();
+    let boundary_mask = if boundary_mask == 0 { 0 } else { (1u32 << boundary_mask) - 1 };
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut hasher = RollingHash::new();
+
+    for (i, &byte) in data.iter().enumerate() {
+        let hash = hasher.roll(byte);
+        let current_len = i + 1 - start;
+
+        if current_len >= params.min_chunk_size
+            && (current_len >= params.max_chunk_size || hash & boundary_mask == 0)
+        {
+            chunks.push(&data[start..=i]);
+            start = i + 1;
+            hasher = RollingHash::new();
+        }
+    }
+
+    if start < data.len() {
+        chunks.push(&data[start..]);
+    }
+
+    chunks
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Content-addressed block store backing chunked rows: chunks live in the
+/// `blocks` tree keyed by their SHA-256 hash, with a reference count in
+/// `block_refs` tracking how many rows (or historical versions) still use
+/// each block.
+pub struct ChunkStore<'a> {
+    backend: &'a dyn StorageBackend,
+}
+
+impl<'a> ChunkStore<'a> {
+    pub fn new(backend: &'a dyn StorageBackend) -> Self {
+        Self { backend }
+    }
+
+    pub fn store_value(&self, value_bytes: &[u8], params: &ChunkingParams) -> Result<Vec<String>> {
+        let mut chunk_hashes = Vec::new();
+
+        for chunk in cut_chunks(value_bytes, params) {
+            let hash_hex = to_hex(&Sha256::digest(chunk));
+
+            if self.backend.get("blocks", hash_hex.as_bytes())?.is_none() {
+                self.backend.put("blocks", hash_hex.as_bytes(), chunk)?;
+            }
+            self.bump_refcount(&hash_hex, 1)?;
+
+            chunk_hashes.push(hash_hex);
+        }
+
+        Ok(chunk_hashes)
+    }
+
+    pub fn load_value(&self, chunk_hashes: &[String]) -> Result<Vec<u8>> {
+        let mut value = Vec::new();
+        for hash_hex in chunk_hashes {
+            if let Some(bytes) = self.backend.get("blocks", hash_hex.as_bytes())? {
+                value.extend_from_slice(&bytes);
+            }
+        }
+        Ok(value)
+    }
+
+    /// Decrement the refcount of every chunk in `chunk_hashes`, garbage
+    /// collecting any block that reaches zero.
+    pub fn release_value(&self, chunk_hashes: &[String]) -> Result<()> {
+        for hash_hex in chunk_hashes {
+            if self.bump_refcount(hash_hex, -1)? <= 0 {
+                self.backend.delete("blocks", hash_hex.as_bytes())?;
+                self.backend.delete("block_refs", hash_hex.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn bump_refcount(&self, hash_hex: &str, delta: i64) -> Result<i64> {
+        let current = match self.backend.get("block_refs", hash_hex.as_bytes())? {
+            Some(bytes) => String::from_utf8_lossy(&bytes).parse::<i64>().unwrap_or(0),
+            None => 0,
+        };
+        let updated = (current + delta).max(0);
+        self.backend
+            .put("block_refs", hash_hex.as_bytes(), updated.to_string().as_bytes())?;
+        Ok(updated)
+    }
+}
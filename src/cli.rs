@@ -1,10 +1,17 @@
-use clap::{Parser, Subcommand};
-use anyhow::Result;
+use clap::{Parser, Subcommand, ValueEnum};
+use anyhow::{anyhow, Result};
 use serde_json::Value;
-use tokio::net::TcpStream;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::io::Write;
+use std::time::Duration;
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
 
 use crate::config::Config;
+use crate::output::OutputFormat;
+use crate::splitter::Splitter;
+
+mod output;
+mod splitter;
 
 #[derive(Parser)]
 #[command(name = "vectra")]
@@ -13,12 +20,21 @@ use crate::config::Config;
 pub struct Cli {
     #[command(subcommand)]
     command: Commands,
-    
+
     #[arg(short, long, default_value = "127.0.0.1")]
     host: String,
-    
+
     #[arg(short, long, default_value = "8080")]
     port: u16,
+
+    /// How result-producing commands render their output.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table, global = true)]
+    format: OutputFormat,
+
+    /// Reused across requests instead of opening a fresh connection pool
+    /// per call - see `make_request`/`get_request`.
+    #[arg(skip)]
+    client: reqwest::Client,
 }
 
 #[derive(Subcommand)]
@@ -93,14 +109,114 @@ enum Commands {
     
     /// Get storage statistics
     Stats,
-    
+
+    /// Manage scheduled re-embedding jobs
+    Job {
+        #[command(subcommand)]
+        command: JobCommands,
+    },
+
+    /// Ask a retrieval-augmented question, streaming the answer as it's generated
+    Rag {
+        /// Question to answer
+        #[arg(value_name = "QUERY")]
+        query: String,
+
+        /// Table to retrieve context from
+        #[arg(short, long, default_value = "documents")]
+        table: String,
+
+        /// Maximum number of context chunks to retrieve
+        #[arg(short, long, default_value = "5")]
+        limit: usize,
+
+        /// Text generation model to answer with
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+
+    /// Bulk-load a file or directory of documents, splitting each into
+    /// overlapping chunks and inserting one row per chunk for embedding
+    Ingest {
+        /// Target table
+        #[arg(value_name = "TABLE")]
+        table: String,
+
+        /// File or directory to ingest
+        #[arg(value_name = "PATH")]
+        path: String,
+
+        /// Maximum characters per chunk
+        #[arg(long, default_value = "1000")]
+        chunk_size: usize,
+
+        /// Trailing characters carried from one chunk into the next
+        #[arg(long, default_value = "200")]
+        chunk_overlap: usize,
+
+        /// Text splitting strategy
+        #[arg(long, value_enum, default_value_t = Splitter::RecursiveCharacter)]
+        splitter: Splitter,
+    },
+
     /// Health check
     Health,
-    
+
     /// Start interactive mode
     Interactive,
 }
 
+#[derive(Subcommand)]
+enum JobCommands {
+    /// Create a recurring re-embedding job
+    Create {
+        /// Job name
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Target table
+        #[arg(short, long)]
+        table: String,
+
+        /// Primary key column
+        #[arg(long)]
+        primary_key: String,
+
+        /// Source text columns to embed, comma-separated
+        #[arg(short, long, value_delimiter = ',')]
+        columns: Vec<String>,
+
+        /// Vector column the embedding is written to
+        #[arg(short, long)]
+        vector_column: String,
+
+        /// Embedding model/transformer name
+        #[arg(long)]
+        transformer: String,
+
+        /// Cron-style schedule, e.g. "*/5 * * * *"
+        #[arg(short, long)]
+        schedule: String,
+    },
+
+    /// List all jobs
+    List,
+
+    /// Force an immediate run, bypassing the schedule
+    Run {
+        /// Job name
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+
+    /// Delete a job
+    Delete {
+        /// Job name
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+}
+
 impl Cli {
     pub async fn run() -> Result<()> {
         let cli = Cli::parse();
@@ -133,6 +249,15 @@ impl Cli {
             Commands::Stats => {
                 cli.get_stats().await?;
             }
+            Commands::Job { command } => {
+                cli.handle_job_command(command).await?;
+            }
+            Commands::Rag { query, table, limit, model } => {
+                cli.rag(&query, &table, limit, model.as_deref()).await?;
+            }
+            Commands::Ingest { table, path, chunk_size, chunk_overlap, splitter } => {
+                cli.ingest(&table, &path, chunk_size, chunk_overlap, splitter).await?;
+            }
             Commands::Health => {
                 cli.health_check().await?;
             }
@@ -150,198 +275,536 @@ impl Cli {
         });
         
         let response = self.make_request("/query", &request).await?;
-        println!("{}", serde_json::to_string_pretty(&response)?);
-        
+        println!("{}", output::render(&response, self.format)?.trim_end());
+
         Ok(())
     }
-    
+
     async fn vector_search(&self, query: &str, limit: usize) -> Result<()> {
         let request = serde_json::json!({
             "query": query,
             "limit": limit
         });
-        
+
         let response = self.make_request("/vector/search", &request).await?;
         println!("Vector Search Results:");
-        println!("{}", serde_json::to_string_pretty(&response)?);
-        
+        println!("{}", output::render(&response, self.format)?.trim_end());
+
         Ok(())
     }
     
+    /// Tails `topic` over a GraphQL-over-WebSocket-style subscription
+    /// protocol: `connection_init`, then `subscribe`, then a loop printing
+    /// each `next` frame's payload until `complete` or Ctrl-C. Reconnects
+    /// with exponential backoff if the socket drops.
     async fn subscribe_stream(&self, topic: &str) -> Result<()> {
+        let url = format!("ws://{}:{}/stream", self.host, self.port);
+        let min_backoff = Duration::from_millis(500);
+        let max_backoff = Duration::from_secs(30);
+        let mut backoff = min_backoff;
+
+        loop {
+            let connected = tokio::select! {
+                result = tokio_tungstenite::connect_async(&url) => result,
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+            };
+
+            let ws_stream = match connected {
+                Ok((ws_stream, _)) => ws_stream,
+                Err(e) => {
+                    eprintln!("failed to connect to {}: {} (retrying in {:?})", url, e, backoff);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(max_backoff);
+                    continue;
+                }
+            };
+            backoff = min_backoff;
+
+            if self.run_subscription(ws_stream, topic).await? {
+                return Ok(());
+            }
+
+            eprintln!("subscription to '{}' dropped, reconnecting...", topic);
+        }
+    }
+
+    /// Drives one WebSocket connection's worth of the subscription
+    /// protocol. Returns `Ok(true)` if the subscription ended (the server
+    /// sent `complete`, or the user pressed Ctrl-C), `Ok(false)` if the
+    /// socket closed and the caller should reconnect.
+    async fn run_subscription(
+        &self,
+        mut ws_stream: tokio_tungstenite::WebSocketStream<
+            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+        >,
+        topic: &str,
+    ) -> Result<bool> {
+        let id = uuid::Uuid::new_v4().to_string();
+
+        ws_stream
+            .send(WsMessage::text(serde_json::json!({ "type": "connection_init" }).to_string()))
+            .await?;
+        ws_stream
+            .send(WsMessage::text(
+                serde_json::json!({ "type": "subscribe", "id": id, "payload": { "topic": topic } })
+                    .to_string(),
+            ))
+            .await?;
+
+        println!("Subscribed to '{}' (id: {})", topic, id);
+
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    let _ = ws_stream
+                        .send(WsMessage::text(serde_json::json!({ "type": "complete", "id": id }).to_string()))
+                        .await;
+                    return Ok(true);
+                }
+                incoming = ws_stream.next() => {
+                    match incoming {
+                        Some(Ok(WsMessage::Text(text))) => {
+                            let Ok(frame) = serde_json::from_str::<Value>(&text) else {
+                                continue;
+                            };
+                            match frame["type"].as_str() {
+                                Some("next") => println!("{}", serde_json::to_string_pretty(&frame["payload"])?),
+                                Some("error") => eprintln!("stream error: {}", frame["payload"]),
+                                Some("complete") => return Ok(true),
+                                _ => {}
+                            }
+                        }
+                        Some(Ok(WsMessage::Close(_))) | None => return Ok(false),
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            eprintln!("stream connection error: {}", e);
+                            return Ok(false);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    
+    async fn create_table(&self, table: &str, schema: &str) -> Result<()> {
         let request = serde_json::json!({
-            "topic": topic
+            "table": table,
+            "schema": schema
         });
-        
-        let response = self.make_request("/stream/subscribe", &request).await?;
-        println!("Stream Subscription Created:");
+
+        let response = self.make_request("/tables", &request).await?;
         println!("{}", serde_json::to_string_pretty(&response)?);
-        
+
         Ok(())
     }
-    
-    async fn create_table(&self, table: &str, schema: &str) -> Result<()> {
-        // This would need to be implemented in the engine
-        println!("Creating table '{}' with schema: {}", table, schema);
-        println!("Note: This feature requires engine implementation");
-        
+
+    async fn insert_data(&self, table: &str, data: &str) -> Result<()> {
+        let data: Value = serde_json::from_str(data)?;
+        let key = data["key"]
+            .as_str()
+            .ok_or_else(|| anyhow!("data must include a \"key\" field"))?
+            .to_string();
+        let request = serde_json::json!({
+            "key": key,
+            "data": data
+        });
+
+        let endpoint = format!("/tables/{}/rows", table);
+        let response = self.make_request(&endpoint, &request).await?;
+        println!("{}", serde_json::to_string_pretty(&response)?);
+
         Ok(())
     }
-    
-    async fn insert_data(&self, table: &str, data: &str) -> Result<()> {
-        // This would need to be implemented in the engine
-        println!("Inserting data into table '{}':", table);
-        println!("{}", data);
-        println!("Note: This feature requires engine implementation");
-        
+
+    /// Reads every file under `path` (or `path` itself if it's a single
+    /// file), splits each into overlapping chunks via `splitter`, and
+    /// inserts one row per chunk through the same `/tables/{table}/rows`
+    /// endpoint `insert_data` uses, so autoembed-aware tables pick the
+    /// chunks up for embedding without any extra plumbing.
+    async fn ingest(
+        &self,
+        table: &str,
+        path: &str,
+        chunk_size: usize,
+        chunk_overlap: usize,
+        splitter: Splitter,
+    ) -> Result<()> {
+        let root = std::path::Path::new(path);
+        let files = Self::collect_files(root)?;
+        if files.is_empty() {
+            return Err(anyhow!("no files found at {}", path));
+        }
+
+        let mut chunks_inserted = 0usize;
+        for file in &files {
+            let document_id = file
+                .strip_prefix(root)
+                .unwrap_or(file)
+                .to_string_lossy()
+                .into_owned();
+            let document_id = if document_id.is_empty() {
+                file.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+            } else {
+                document_id
+            };
+
+            let text = std::fs::read_to_string(file)?;
+            let chunks = splitter::split(&text, splitter, chunk_size, chunk_overlap);
+
+            for (chunk_index, chunk_text) in chunks.into_iter().enumerate() {
+                let key = format!("{}:{}", document_id, chunk_index);
+                let data = serde_json::json!({
+                    "text": chunk_text,
+                    "document_id": document_id,
+                    "chunk_index": chunk_index,
+                    "source": file.to_string_lossy(),
+                });
+                let request = serde_json::json!({ "key": key, "data": data });
+                let endpoint = format!("/tables/{}/rows", table);
+                self.make_request(&endpoint, &request).await?;
+                chunks_inserted += 1;
+            }
+        }
+
+        println!(
+            "Ingested {} chunk(s) from {} file(s) into \"{}\"",
+            chunks_inserted,
+            files.len(),
+            table
+        );
         Ok(())
     }
-    
+
+    /// Recursively collects every regular file under `root`, or returns
+    /// `[root]` if it's already a file.
+    fn collect_files(root: &std::path::Path) -> Result<Vec<std::path::PathBuf>> {
+        if root.is_file() {
+            return Ok(vec![root.to_path_buf()]);
+        }
+
+        let mut files = Vec::new();
+        let mut stack = vec![root.to_path_buf()];
+        while let Some(dir) = stack.pop() {
+            for entry in std::fs::read_dir(&dir)? {
+                let entry_path = entry?.path();
+                if entry_path.is_dir() {
+                    stack.push(entry_path);
+                } else {
+                    files.push(entry_path);
+                }
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
     async fn create_index(&self, table: &str, column: &str) -> Result<()> {
-        // This would need to be implemented in the engine
-        println!("Creating vector index on table '{}', column '{}'", table, column);
-        println!("Note: This feature requires engine implementation");
-        
+        let request = serde_json::json!({
+            "table": table,
+            "column": column
+        });
+
+        let response = self.make_request("/index", &request).await?;
+        println!("{}", serde_json::to_string_pretty(&response)?);
+
         Ok(())
     }
-    
+
     async fn list_tables(&self) -> Result<()> {
-        // This would need to be implemented in the engine
-        println!("Listing tables:");
-        println!("Note: This feature requires engine implementation");
-        
+        let response = self.get_request("/tables").await?;
+        println!("{}", output::render(&response, self.format)?.trim_end());
+
         Ok(())
     }
-    
+
     async fn table_info(&self, table: &str) -> Result<()> {
-        // This would need to be implemented in the engine
-        println!("Table info for '{}':", table);
-        println!("Note: This feature requires engine implementation");
-        
+        let endpoint = format!("/tables/{}", table);
+        let response = self.get_request(&endpoint).await?;
+        println!("{}", output::render(&response, self.format)?.trim_end());
+
         Ok(())
     }
-    
+
     async fn get_stats(&self) -> Result<()> {
-        // This would need to be implemented in the engine
-        println!("Storage statistics:");
-        println!("Note: This feature requires engine implementation");
-        
+        let response = self.get_request("/stats").await?;
+        println!("{}", output::render(&response, self.format)?.trim_end());
+
+        Ok(())
+    }
+
+    async fn handle_job_command(&self, command: JobCommands) -> Result<()> {
+        match command {
+            JobCommands::Create {
+                name,
+                table,
+                primary_key,
+                columns,
+                vector_column,
+                transformer,
+                schedule,
+            } => {
+                let request = serde_json::json!({
+                    "name": name,
+                    "table": table,
+                    "primary_key": primary_key,
+                    "source_columns": columns,
+                    "vector_column": vector_column,
+                    "transformer": transformer,
+                    "schedule": schedule,
+                });
+
+                let response = self.make_request("/jobs", &request).await?;
+                println!("{}", serde_json::to_string_pretty(&response)?);
+            }
+            JobCommands::List => {
+                let response = self.get_request("/jobs").await?;
+                println!("{}", serde_json::to_string_pretty(&response)?);
+            }
+            JobCommands::Run { name } => {
+                let endpoint = format!("/jobs/{}/run", name);
+                let response = self.make_request(&endpoint, &serde_json::json!({})).await?;
+                println!("{}", serde_json::to_string_pretty(&response)?);
+            }
+            JobCommands::Delete { name } => {
+                let endpoint = format!("/jobs/{}", name);
+                let response = self.delete_request(&endpoint).await?;
+                println!("{}", serde_json::to_string_pretty(&response)?);
+            }
+        }
+
         Ok(())
     }
     
+    /// Performs a RAG query against `/rag`: prints the retrieved sources,
+    /// then streams the answer token-by-token as SSE `token` events arrive,
+    /// and finally prints the citations from the closing `citations` event.
+    async fn rag(&self, query: &str, table: &str, limit: usize, model: Option<&str>) -> Result<()> {
+        let url = format!("http://{}:{}/rag", self.host, self.port);
+        let mut request = serde_json::json!({
+            "query": query,
+            "table": table,
+            "limit": limit,
+        });
+        if let Some(model) = model {
+            request["model"] = serde_json::json!(model);
+        }
+
+        let response = self.client.post(&url).json(&request).send().await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("request to {} failed with status {}: {}", url, status, body));
+        }
+
+        let mut buffer = String::new();
+        let mut stream = response.bytes_stream();
+
+        while let Some(chunk) = stream.next().await {
+            buffer.push_str(&String::from_utf8_lossy(&chunk?));
+
+            while let Some(boundary) = buffer.find("\n\n") {
+                let event_block: String = buffer.drain(..boundary + 2).collect();
+                self.print_rag_event(&event_block)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parses one `event: <type>\ndata: <payload>\n\n` SSE block and prints
+    /// it according to the stage it belongs to (sources/token/citations).
+    fn print_rag_event(&self, block: &str) -> Result<()> {
+        let mut event_type = "message".to_string();
+        let mut data = String::new();
+
+        for line in block.lines() {
+            if let Some(value) = line.strip_prefix("event:") {
+                event_type = value.trim().to_string();
+            } else if let Some(value) = line.strip_prefix("data:") {
+                data.push_str(value.trim_start());
+            }
+        }
+
+        match event_type.as_str() {
+            "sources" => {
+                println!("Sources:");
+                let sources: Value = serde_json::from_str(&data).unwrap_or(Value::Null);
+                for source in sources.as_array().into_iter().flatten() {
+                    let text = source["metadata"]["text"].as_str().unwrap_or("");
+                    println!("  [{}] score={} {}", source["id"], source["score"], text);
+                }
+                print!("\nAnswer: ");
+                std::io::stdout().flush()?;
+            }
+            "token" => {
+                print!("{}", data);
+                std::io::stdout().flush()?;
+            }
+            "citations" => {
+                println!();
+                println!("Citations: {}", data);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
     async fn health_check(&self) -> Result<()> {
-        let response = self.make_request("/health", &serde_json::json!({})).await?;
+        let response = self.get_request("/health").await?;
         println!("Health Check:");
         println!("{}", serde_json::to_string_pretty(&response)?);
         
         Ok(())
     }
     
+    /// Path to the persisted REPL history file, `~/.vectra_history`. Falls
+    /// back to the current directory if `HOME` isn't set rather than
+    /// failing interactive mode outright.
+    fn history_path() -> std::path::PathBuf {
+        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        std::path::Path::new(&home).join(".vectra_history")
+    }
+
     async fn interactive_mode(&self) -> Result<()> {
         println!("VectraEdge Interactive Mode");
-        println!("Type 'help' for commands, 'quit' to exit");
+        println!("Type 'help' for commands, '\\?' for meta-commands, 'quit' to exit");
         println!();
-        
+
+        let history_path = Self::history_path();
+        let mut editor: rustyline::DefaultEditor = rustyline::DefaultEditor::new()?;
+        let _ = editor.load_history(&history_path);
+
+        let mut timing = false;
+
         loop {
-            print!("vectra> ");
-            std::io::stdout().flush()?;
-            
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input)?;
-            let input = input.trim();
-            
+            let line = match editor.readline("vectra> ") {
+                Ok(line) => line,
+                Err(rustyline::error::ReadlineError::Interrupted) | Err(rustyline::error::ReadlineError::Eof) => break,
+                Err(e) => return Err(e.into()),
+            };
+
+            let input = line.trim();
             if input.is_empty() {
                 continue;
             }
-            
+            editor.add_history_entry(input)?;
+
             if input == "quit" || input == "exit" {
                 break;
             }
-            
+
             if input == "help" {
                 self.show_help();
                 continue;
             }
-            
-            // Try to execute as SQL query
-            if input.to_lowercase().starts_with("select") || 
-               input.to_lowercase().starts_with("create") ||
-               input.to_lowercase().starts_with("insert") ||
-               input.to_lowercase().starts_with("update") ||
-               input.to_lowercase().starts_with("delete") {
-                if let Err(e) = self.execute_query(input).await {
-                    eprintln!("Error: {}", e);
-                }
-            } else {
-                println!("Unknown command. Type 'help' for available commands.");
+
+            if let Some(rest) = input.strip_prefix('\\') {
+                self.handle_meta_command(rest.trim(), &mut timing).await;
+                continue;
+            }
+
+            // Anything that isn't a meta-command or a REPL built-in is
+            // forwarded to /query as-is, so analytical expressions and
+            // SHOW-style statements work even though they aren't one of
+            // the recognized SQL verbs.
+            let start = std::time::Instant::now();
+            if let Err(e) = self.execute_query(input).await {
+                eprintln!("Error: {}", e);
+            }
+            if timing {
+                println!("Time: {:.3}ms", start.elapsed().as_secs_f64() * 1000.0);
             }
         }
-        
+
+        let _ = editor.save_history(&history_path);
         Ok(())
     }
-    
+
+    /// Dispatches a psql-style backslash meta-command (the leading `\` is
+    /// already stripped). Unrecognized commands print a hint rather than
+    /// erroring, matching `show_help`'s tone for unknown top-level input.
+    async fn handle_meta_command(&self, command: &str, timing: &mut bool) {
+        let mut parts = command.splitn(2, char::is_whitespace);
+        let verb = parts.next().unwrap_or("");
+        let arg = parts.next().map(str::trim).unwrap_or("");
+
+        let result = match verb {
+            "dt" => self.list_tables().await,
+            "d" if !arg.is_empty() => self.table_info(arg).await,
+            "stats" => self.get_stats().await,
+            "timing" => {
+                *timing = !*timing;
+                println!("Timing is {}.", if *timing { "on" } else { "off" });
+                Ok(())
+            }
+            "?" => {
+                self.show_help();
+                Ok(())
+            }
+            _ => {
+                println!("Unknown meta-command: \\{}. Type '\\?' for help.", command);
+                Ok(())
+            }
+        };
+
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+        }
+    }
+
     fn show_help(&self) {
         println!("Available commands:");
         println!("  SQL queries: SELECT, CREATE, INSERT, UPDATE, DELETE");
+        println!("  Anything else is still forwarded to /query (e.g. SHOW-style statements)");
         println!("  help        - Show this help");
         println!("  quit/exit   - Exit interactive mode");
         println!();
+        println!("Meta-commands:");
+        println!("  \\dt         - List tables");
+        println!("  \\d <table>  - Show schema/column info for <table>");
+        println!("  \\stats      - Show storage statistics");
+        println!("  \\timing     - Toggle printing of per-query elapsed time");
+        println!("  \\?          - Show this help");
+        println!();
     }
     
+    /// POSTs `data` to `endpoint` and returns the decoded JSON response.
     async fn make_request(&self, endpoint: &str, data: &Value) -> Result<Value> {
         let url = format!("http://{}:{}{}", self.host, self.port, endpoint);
-        
-        // For now, we'll just return mock data
-        // In a real implementation, this would make an HTTP request
-        
-        match endpoint {
-            "/query" => {
-                Ok(serde_json::json!({
-                    "rows": 1,
-                    "data": [
-                        {
-                            "result": "Query executed successfully",
-                            "sql": data["query"]
-                        }
-                    ]
-                }))
-            }
-            "/vector/search" => {
-                Ok(serde_json::json!({
-                    "results": [
-                        {
-                            "id": 1,
-                            "score": 0.95,
-                            "metadata": {
-                                "text": "Sample result",
-                                "table": "docs"
-                            }
-                        }
-                    ],
-                    "query": data["query"],
-                    "limit": data["limit"]
-                }))
-            }
-            "/stream/subscribe" => {
-                Ok(serde_json::json!({
-                    "subscription_id": "sub_12345",
-                    "topic": data["topic"],
-                    "status": "active"
-                }))
-            }
-            "/health" => {
-                Ok(serde_json::json!({
-                    "status": "healthy",
-                    "version": env!("CARGO_PKG_VERSION"),
-                    "timestamp": chrono::Utc::now().to_rfc3339()
-                }))
-            }
-            _ => {
-                Ok(serde_json::json!({
-                    "error": "Unknown endpoint"
-                }))
-            }
+        let response = self.client.post(&url).json(data).send().await?;
+        Self::decode_response(&url, response).await
+    }
+
+    /// GETs `endpoint` and returns the decoded JSON response. Used for the
+    /// read-only endpoints (`/tables`, `/tables/{table}`, `/stats`,
+    /// `/health`) that don't take a request body.
+    async fn get_request(&self, endpoint: &str) -> Result<Value> {
+        let url = format!("http://{}:{}{}", self.host, self.port, endpoint);
+        let response = self.client.get(&url).send().await?;
+        Self::decode_response(&url, response).await
+    }
+
+    /// Issues a DELETE to `endpoint` and returns the decoded JSON response.
+    /// Used for `job delete`.
+    async fn delete_request(&self, endpoint: &str) -> Result<Value> {
+        let url = format!("http://{}:{}{}", self.host, self.port, endpoint);
+        let response = self.client.delete(&url).send().await?;
+        Self::decode_response(&url, response).await
+    }
+
+    async fn decode_response(url: &str, response: reqwest::Response) -> Result<Value> {
+        let status = response.status();
+        let body: Value = response
+            .json()
+            .await
+            .unwrap_or_else(|_| serde_json::json!({ "error": "response was not valid JSON" }));
+
+        if !status.is_success() {
+            return Err(anyhow!("request to {} failed with status {}: {}", url, status, body));
         }
+
+        Ok(body)
     }
 }
 
@@ -0,0 +1,170 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// How a result-producing command renders its response. `Table` is the
+/// default for interactive use; `Json`/`Ndjson`/`Csv` are for piping into
+/// other tools.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Ndjson,
+    Csv,
+}
+
+/// Renders `value` per `format`. `value` may be a single object, an array
+/// of objects (the common case for query/search results), or a scalar.
+/// `Table`/`Csv` use the union of every row's keys as their columns, so
+/// heterogeneous rows line up under one header instead of erroring, with
+/// missing fields rendered as empty cells.
+pub fn render(value: &Value, format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(value)?),
+        OutputFormat::Ndjson => Ok(render_ndjson(value)?),
+        OutputFormat::Table => Ok(render_table(value)),
+        OutputFormat::Csv => Ok(render_csv(value)),
+    }
+}
+
+fn rows_of(value: &Value) -> Vec<Value> {
+    match value {
+        Value::Array(items) => items.clone(),
+        other => vec![other.clone()],
+    }
+}
+
+fn columns_of(rows: &[Value]) -> Vec<String> {
+    let mut columns = BTreeSet::new();
+    for row in rows {
+        if let Value::Object(map) = row {
+            for key in map.keys() {
+                columns.insert(key.clone());
+            }
+        }
+    }
+    columns.into_iter().collect()
+}
+
+/// Renders a cell's value compactly: strings pass through unquoted, every
+/// other JSON type (including nested objects/arrays) serializes as compact
+/// JSON so it still fits on one line.
+fn cell(row: &Value, column: &str) -> String {
+    match row.get(column) {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn render_ndjson(value: &Value) -> Result<String> {
+    let mut out = String::new();
+    for row in rows_of(value) {
+        out.push_str(&serde_json::to_string(&row)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn render_table(value: &Value) -> String {
+    let rows = rows_of(value);
+    let columns = columns_of(&rows);
+    if columns.is_empty() {
+        return serde_json::to_string_pretty(value).unwrap_or_default();
+    }
+
+    let cells: Vec<Vec<String>> = rows.iter().map(|row| columns.iter().map(|c| cell(row, c)).collect()).collect();
+
+    let mut widths: Vec<usize> = columns.iter().map(|c| c.len()).collect();
+    for row in &cells {
+        for (i, value) in row.iter().enumerate() {
+            widths[i] = widths[i].max(value.len());
+        }
+    }
+
+    let pad_row = |fields: &[String]| -> String {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| format!("{:width$}", field, width = widths[i]))
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    let mut out = String::new();
+    out.push_str(pad_row(&columns).trim_end());
+    out.push('\n');
+    out.push_str(&widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("  "));
+    out.push('\n');
+    for row in &cells {
+        out.push_str(pad_row(row).trim_end());
+        out.push('\n');
+    }
+
+    out
+}
+
+fn render_csv(value: &Value) -> String {
+    let rows = rows_of(value);
+    let columns = columns_of(&rows);
+
+    let mut out = String::new();
+    out.push_str(&columns.iter().map(|c| csv_escape(c)).collect::<Vec<_>>().join(","));
+    out.push_str("\r\n");
+    for row in &rows {
+        let fields: Vec<String> = columns.iter().map(|c| csv_escape(&cell(row, c))).collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') || field.contains('\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_table_aligns_union_of_keys() {
+        let value = serde_json::json!([
+            {"id": 1, "name": "alice"},
+            {"id": 2, "name": "bob", "extra": "x"}
+        ]);
+
+        let table = render_table(&value);
+        let mut lines = table.lines();
+        assert_eq!(lines.next().unwrap(), "extra  id  name");
+        assert!(table.contains("alice"));
+        assert!(table.contains("x"));
+    }
+
+    #[test]
+    fn test_render_csv_escapes_commas_and_quotes() {
+        let value = serde_json::json!([{"note": "hello, \"world\""}]);
+        let csv = render_csv(&value);
+        assert_eq!(csv, "note\r\n\"hello, \"\"world\"\"\"\r\n");
+    }
+
+    #[test]
+    fn test_render_ndjson_one_object_per_line() {
+        let value = serde_json::json!([{"a": 1}, {"a": 2}]);
+        let ndjson = render_ndjson(&value).unwrap();
+        assert_eq!(ndjson, "{\"a\":1}\n{\"a\":2}\n");
+    }
+
+    #[test]
+    fn test_render_table_on_single_object() {
+        let value = serde_json::json!({"status": "ok"});
+        let table = render_table(&value);
+        assert!(table.contains("status"));
+        assert!(table.contains("ok"));
+    }
+}
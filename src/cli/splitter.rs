@@ -0,0 +1,145 @@
+use clap::ValueEnum;
+
+/// Text splitting strategy for `vectra ingest`.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Splitter {
+    RecursiveCharacter,
+    Fixed,
+}
+
+/// Splits `text` into overlapping chunks of about `chunk_size` characters,
+/// each carrying up to `chunk_overlap` trailing characters from the
+/// previous chunk so embeddings don't lose context at chunk boundaries.
+/// `Fixed` chunks never exceed `chunk_size`; a `RecursiveCharacter` chunk
+/// that starts with carried overlap can exceed it by up to
+/// `chunk_overlap + 1` characters (the overlap plus a joining space).
+pub fn split(text: &str, splitter: Splitter, chunk_size: usize, chunk_overlap: usize) -> Vec<String> {
+    match splitter {
+        Splitter::RecursiveCharacter => split_recursive_character(text, chunk_size, chunk_overlap),
+        Splitter::Fixed => split_fixed(text, chunk_size, chunk_overlap),
+    }
+}
+
+/// Separators tried in descending priority: paragraph breaks, newlines,
+/// sentence boundaries, then words. Whatever is left over after the last
+/// separator is hard-split on individual characters.
+const SEPARATORS: &[&str] = &["\n\n", "\n", ". ", " "];
+
+fn split_recursive_character(text: &str, chunk_size: usize, chunk_overlap: usize) -> Vec<String> {
+    let pieces = split_into_pieces(text, SEPARATORS, chunk_size);
+    pack_pieces(&pieces, chunk_size, chunk_overlap)
+}
+
+/// Recursively breaks `text` into pieces no larger than `chunk_size` by
+/// trying `separators` in order; a piece still too large after the last
+/// separator is hard-split on characters.
+fn split_into_pieces(text: &str, separators: &[&str], chunk_size: usize) -> Vec<String> {
+    if text.chars().count() <= chunk_size {
+        return vec![text.to_string()];
+    }
+
+    let Some((separator, rest)) = separators.split_first() else {
+        return hard_split(text, chunk_size);
+    };
+
+    if !text.contains(separator) {
+        return split_into_pieces(text, rest, chunk_size);
+    }
+
+    text.split(separator)
+        .filter(|piece| !piece.is_empty())
+        .flat_map(|piece| split_into_pieces(piece, rest, chunk_size))
+        .collect()
+}
+
+fn hard_split(text: &str, chunk_size: usize) -> Vec<String> {
+    text.chars()
+        .collect::<Vec<_>>()
+        .chunks(chunk_size.max(1))
+        .map(|chars| chars.iter().collect())
+        .collect()
+}
+
+/// Greedily packs `pieces` back together up to `chunk_size` characters,
+/// carrying the trailing `chunk_overlap` characters of each finished chunk
+/// into the start of the next so context survives the boundary.
+fn pack_pieces(pieces: &[String], chunk_size: usize, chunk_overlap: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for piece in pieces {
+        let joiner = usize::from(!current.is_empty() && !current.ends_with(char::is_whitespace));
+        if !current.is_empty() && current.chars().count() + joiner + piece.chars().count() > chunk_size {
+            chunks.push(current.clone());
+            current = trailing_chars(&current, chunk_overlap);
+        }
+        if !current.is_empty() && !current.ends_with(char::is_whitespace) {
+            current.push(' ');
+        }
+        current.push_str(piece);
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+fn trailing_chars(text: &str, count: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let start = chars.len().saturating_sub(count);
+    chars[start..].iter().collect()
+}
+
+fn split_fixed(text: &str, chunk_size: usize, chunk_overlap: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.is_empty() {
+        return Vec::new();
+    }
+
+    let step = chunk_size.saturating_sub(chunk_overlap).max(1);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        let end = (start + chunk_size).min(chars.len());
+        chunks.push(chars[start..end].iter().collect());
+        if end == chars.len() {
+            break;
+        }
+        start += step;
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_splits_with_overlap() {
+        let chunks = split_fixed("abcdefghij", 4, 1);
+        assert_eq!(chunks, vec!["abcd", "defg", "ghij"]);
+    }
+
+    #[test]
+    fn test_fixed_single_chunk_when_shorter_than_size() {
+        let chunks = split_fixed("short", 100, 10);
+        assert_eq!(chunks, vec!["short"]);
+    }
+
+    #[test]
+    fn test_recursive_character_respects_chunk_size() {
+        let text = "one two three four five six seven eight nine ten";
+        let chunks = split_recursive_character(text, 12, 3);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 12 + 3, "chunk too long: {:?}", chunk);
+        }
+    }
+
+    #[test]
+    fn test_recursive_character_carries_overlap() {
+        let text = "paragraph one here.\n\nparagraph two here.\n\nparagraph three here.";
+        let chunks = split_recursive_character(text, 20, 5);
+        assert!(chunks.len() > 1);
+    }
+}
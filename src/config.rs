@@ -3,14 +3,54 @@ use std::env;
 use std::path::PathBuf;
 use anyhow::Result;
 
+/// Config file names checked at each directory level, in priority order, by
+/// `Config::resolved_path` - first under `config/`, then bare in the
+/// directory itself.
+const CONFIG_FILENAMES: &[&str] = &["vectra.toml", "vectra.yaml", "vectra.json"];
+
+/// Known top-level section keys of `Config`/`PartialConfig`. Any other
+/// top-level table in a config file is treated as a profile name (e.g.
+/// `prod`, `staging`) - see `Config::layer_profiles`.
+const CONFIG_SECTION_KEYS: &[&str] = &[
+    "server",
+    "storage",
+    "vector_search",
+    "streaming",
+    "ai",
+    "logging",
+    "otlp",
+];
+
+/// Pairs a loaded value with the absolute path it came from, so downstream
+/// error messages (e.g. `validate`) can point an operator at the exact file
+/// to edit instead of leaving them to guess which of several candidate
+/// paths won.
+#[derive(Debug, Clone)]
+pub struct WithPath<T> {
+    pub value: T,
+    pub path: PathBuf,
+}
+
+impl<T> std::ops::Deref for WithPath<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Active profile name (`VECTRA_PROFILE`, default `"default"`) that was
+    /// layered over the base sections - see `Config::active_profile`.
+    #[serde(default = "Config::default_profile")]
+    pub profile: String,
     pub server: ServerConfig,
     pub storage: StorageConfig,
     pub vector_search: VectorSearchConfig,
     pub streaming: StreamingConfig,
     pub ai: AIConfig,
     pub logging: LoggingConfig,
+    pub otlp: OtlpConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,11 +63,36 @@ pub struct ServerConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StorageConfig {
+    pub backend: crate::storage_backend::StorageBackendKind,
     pub rocksdb_path: Option<String>,
     pub sled_path: Option<String>,
+    pub lmdb_path: Option<String>,
+    pub sqlite_path: Option<String>,
     pub data_dir: String,
     pub max_memory_mb: usize,
     pub compression: bool,
+    pub chunking: ChunkingConfig,
+}
+
+/// Content-defined chunking, enabled per table so small rows keep the
+/// cheaper inline path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkingConfig {
+    pub enabled_tables: Vec<String>,
+    pub min_chunk_size: usize,
+    pub max_chunk_size: usize,
+    pub target_chunk_size: usize,
+}
+
+impl Default for ChunkingConfig {
+    fn default() -> Self {
+        Self {
+            enabled_tables: Vec::new(),
+            min_chunk_size: 4 * 1024,
+            max_chunk_size: 64 * 1024,
+            target_chunk_size: 16 * 1024,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,7 +101,44 @@ pub struct VectorSearchConfig {
     pub ef_construction: usize,
     pub ef: usize,
     pub dimension: usize,
-    pub distance_metric: String,
+    pub distance_metric: DistanceMetric,
+}
+
+/// Distance metric used by HNSW vector search. Deserializes
+/// case-insensitively from the existing string forms (`"cosine"`,
+/// `"euclidean"`, `"dot_product"`/`"dotproduct"`) so existing config files
+/// keep working; an unrecognized value now fails at load time instead of
+/// silently reaching the HNSW layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistanceMetric {
+    Cosine,
+    Euclidean,
+    DotProduct,
+}
+
+impl std::str::FromStr for DistanceMetric {
+    type Err = String;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "cosine" => Ok(Self::Cosine),
+            "euclidean" => Ok(Self::Euclidean),
+            "dot_product" | "dotproduct" => Ok(Self::DotProduct),
+            other => Err(format!(
+                "unknown distance metric {:?}, expected one of: cosine, euclidean, dot_product",
+                other
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DistanceMetric {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,24 +156,151 @@ pub struct AIConfig {
     pub text_model: String,
     pub max_tokens: usize,
     pub temperature: f32,
+    /// Which `EmbeddingProvider` backs the active embedding model.
+    pub embedding_provider: EmbeddingProviderKind,
+    /// Base URL for an OpenAI-compatible embeddings API, used when
+    /// `embedding_provider` is `OpenAiCompatible`.
+    pub openai_base_url: String,
+    pub openai_api_key: Option<String>,
+    /// Path to a local `.onnx` model file, used when `embedding_provider`
+    /// is `Onnx`.
+    pub onnx_model_path: Option<String>,
+    /// How long `crate::queue::EmbeddingQueue` waits for more requests to
+    /// arrive before flushing a batch to the provider.
+    pub embedding_queue_debounce_ms: u64,
+    /// Maximum size, in bytes, of a chunk produced by
+    /// `crate::splitter::TextSplitter` before a document is embedded.
+    pub embedding_chunk_size: usize,
+    /// Bytes of overlap between consecutive chunks.
+    pub embedding_chunk_overlap: usize,
+    /// How often `crate::ai::AIRuntime`'s background health watcher probes
+    /// each registered embedding provider.
+    pub model_health_probe_interval_ms: u64,
+    /// How long `generate_embedding`/`generate_text` wait for their model's
+    /// first health probe to land before giving up.
+    pub model_health_ready_timeout_ms: u64,
+    /// Maximum entries the in-memory embedding cache keeps before evicting,
+    /// across all shards - see `crate::cache::CacheConfig::max_entries`.
+    pub embedding_cache_max_entries: usize,
+    /// Soft memory budget, in megabytes, for the in-memory embedding cache.
+    pub embedding_cache_max_memory_mb: usize,
+}
+
+/// Backend an `EmbeddingProvider` talks to. See `crate::embedding`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EmbeddingProviderKind {
+    Ollama,
+    Onnx,
+    OpenAiCompatible,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoggingConfig {
     pub level: String,
-    pub format: String,
-    pub output: String,
+    pub format: LogFormat,
+    pub output: LogOutput,
+}
+
+/// Log record format. Deserializes case-insensitively from the existing
+/// string forms (`"json"`, `"pretty"`, `"compact"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    Json,
+    Pretty,
+    Compact,
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = String;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "pretty" => Ok(Self::Pretty),
+            "compact" => Ok(Self::Compact),
+            other => Err(format!(
+                "unknown log format {:?}, expected one of: json, pretty, compact",
+                other
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LogFormat {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Where log output is written. Deserializes case-insensitively from the
+/// existing string forms: `"stdout"`/`"stderr"` select the matching stream,
+/// any other value is treated as a file path, preserving backward
+/// compatibility with configs that set `output` to a log file path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogOutput {
+    Stdout,
+    Stderr,
+    File(PathBuf),
+}
+
+impl Serialize for LogOutput {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            LogOutput::Stdout => serializer.serialize_str("stdout"),
+            LogOutput::Stderr => serializer.serialize_str("stderr"),
+            LogOutput::File(path) => serializer.serialize_str(&path.display().to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LogOutput {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        Ok(match raw.to_lowercase().as_str() {
+            "stdout" => LogOutput::Stdout,
+            "stderr" => LogOutput::Stderr,
+            _ => LogOutput::File(PathBuf::from(raw)),
+        })
+    }
+}
+
+/// Push-based metrics export, as an alternative to `/metrics`'s
+/// pull-based Prometheus scraping - suited to short-lived jobs and
+/// collectors behind NAT that can't be scraped directly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtlpConfig {
+    pub enabled: bool,
+    /// Base URL of the OTLP collector, e.g. `http://localhost:4318`.
+    /// `/v1/metrics` is appended when pushing.
+    pub endpoint: String,
+    pub push_interval_secs: u64,
+    /// `service.name` resource attribute attached to every exported metric.
+    pub service_name: String,
+    /// `service.instance.id` resource attribute, identifying this process
+    /// among others running the same service.
+    pub instance_id: String,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            profile: Self::default_profile(),
             server: ServerConfig::default(),
             storage: StorageConfig::default(),
             vector_search: VectorSearchConfig::default(),
             streaming: StreamingConfig::default(),
             ai: AIConfig::default(),
             logging: LoggingConfig::default(),
+            otlp: OtlpConfig::default(),
         }
     }
 }
@@ -90,11 +319,15 @@ impl Default for ServerConfig {
 impl Default for StorageConfig {
     fn default() -> Self {
         Self {
+            backend: crate::storage_backend::StorageBackendKind::RocksDb,
             rocksdb_path: Some("./data/rocksdb".to_string()),
             sled_path: Some("./data/sled".to_string()),
+            lmdb_path: Some("./data/lmdb".to_string()),
+            sqlite_path: Some("./data/vectra.sqlite".to_string()),
             data_dir: "./data".to_string(),
             max_memory_mb: 1024,
             compression: true,
+            chunking: ChunkingConfig::default(),
         }
     }
 }
@@ -106,7 +339,7 @@ impl Default for VectorSearchConfig {
             ef_construction: 200,
             ef: 50,
             dimension: 384,
-            distance_metric: "cosine".to_string(),
+            distance_metric: DistanceMetric::Cosine,
         }
     }
 }
@@ -130,6 +363,17 @@ impl Default for AIConfig {
             text_model: "llama2".to_string(),
             max_tokens: 2048,
             temperature: 0.7,
+            embedding_provider: EmbeddingProviderKind::Ollama,
+            openai_base_url: "https://api.openai.com/v1".to_string(),
+            openai_api_key: None,
+            onnx_model_path: None,
+            embedding_queue_debounce_ms: 10,
+            embedding_chunk_size: 1024,
+            embedding_chunk_overlap: 128,
+            model_health_probe_interval_ms: 30_000,
+            model_health_ready_timeout_ms: 5_000,
+            embedding_cache_max_entries: 10_000,
+            embedding_cache_max_memory_mb: 200,
         }
     }
 }
@@ -138,135 +382,566 @@ impl Default for LoggingConfig {
     fn default() -> Self {
         Self {
             level: "info".to_string(),
-            format: "json".to_string(),
-            output: "stdout".to_string(),
+            format: LogFormat::Json,
+            output: LogOutput::Stdout,
+        }
+    }
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:4318".to_string(),
+            push_interval_secs: 30,
+            service_name: "vectraedge".to_string(),
+            instance_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+}
+
+/// Implemented by `Config` and each of its sections so a partial override
+/// layer (a config file, environment variables, ...) can be folded in
+/// field-by-field: a field only overrides `self` when the override
+/// explicitly set it, so an earlier layer's value survives when a later
+/// layer is silent about that field. See `Config::load` for the layering
+/// order this enables.
+pub trait Merge {
+    type Partial;
+    fn merge(self, partial: Self::Partial) -> Self;
+}
+
+/// Mirrors `Config` with every field wrapped in `Option`, representing one
+/// override layer. `None` means "this layer didn't set this field", not
+/// "set to the default" - see `Config::from_file`/`Config::from_env`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PartialConfig {
+    pub server: Option<PartialServerConfig>,
+    pub storage: Option<PartialStorageConfig>,
+    pub vector_search: Option<PartialVectorSearchConfig>,
+    pub streaming: Option<PartialStreamingConfig>,
+    pub ai: Option<PartialAIConfig>,
+    pub logging: Option<PartialLoggingConfig>,
+    pub otlp: Option<PartialOtlpConfig>,
+}
+
+impl Merge for Config {
+    type Partial = PartialConfig;
+    fn merge(self, partial: Self::Partial) -> Self {
+        Self {
+            profile: self.profile,
+            server: match partial.server {
+                Some(p) => self.server.merge(p),
+                None => self.server,
+            },
+            storage: match partial.storage {
+                Some(p) => self.storage.merge(p),
+                None => self.storage,
+            },
+            vector_search: match partial.vector_search {
+                Some(p) => self.vector_search.merge(p),
+                None => self.vector_search,
+            },
+            streaming: match partial.streaming {
+                Some(p) => self.streaming.merge(p),
+                None => self.streaming,
+            },
+            ai: match partial.ai {
+                Some(p) => self.ai.merge(p),
+                None => self.ai,
+            },
+            logging: match partial.logging {
+                Some(p) => self.logging.merge(p),
+                None => self.logging,
+            },
+            otlp: match partial.otlp {
+                Some(p) => self.otlp.merge(p),
+                None => self.otlp,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PartialServerConfig {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub workers: Option<usize>,
+    pub max_connections: Option<usize>,
+}
+
+impl Merge for ServerConfig {
+    type Partial = PartialServerConfig;
+    fn merge(self, partial: Self::Partial) -> Self {
+        Self {
+            host: partial.host.unwrap_or(self.host),
+            port: partial.port.unwrap_or(self.port),
+            workers: partial.workers.unwrap_or(self.workers),
+            max_connections: partial.max_connections.unwrap_or(self.max_connections),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PartialStorageConfig {
+    pub backend: Option<crate::storage_backend::StorageBackendKind>,
+    pub rocksdb_path: Option<String>,
+    pub sled_path: Option<String>,
+    pub lmdb_path: Option<String>,
+    pub sqlite_path: Option<String>,
+    pub data_dir: Option<String>,
+    pub max_memory_mb: Option<usize>,
+    pub compression: Option<bool>,
+    pub chunking: Option<PartialChunkingConfig>,
+}
+
+impl Merge for StorageConfig {
+    type Partial = PartialStorageConfig;
+    fn merge(self, partial: Self::Partial) -> Self {
+        Self {
+            backend: partial.backend.unwrap_or(self.backend),
+            rocksdb_path: partial.rocksdb_path.or(self.rocksdb_path),
+            sled_path: partial.sled_path.or(self.sled_path),
+            lmdb_path: partial.lmdb_path.or(self.lmdb_path),
+            sqlite_path: partial.sqlite_path.or(self.sqlite_path),
+            data_dir: partial.data_dir.unwrap_or(self.data_dir),
+            max_memory_mb: partial.max_memory_mb.unwrap_or(self.max_memory_mb),
+            compression: partial.compression.unwrap_or(self.compression),
+            chunking: match partial.chunking {
+                Some(p) => self.chunking.merge(p),
+                None => self.chunking,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PartialChunkingConfig {
+    pub enabled_tables: Option<Vec<String>>,
+    pub min_chunk_size: Option<usize>,
+    pub max_chunk_size: Option<usize>,
+    pub target_chunk_size: Option<usize>,
+}
+
+impl Merge for ChunkingConfig {
+    type Partial = PartialChunkingConfig;
+    fn merge(self, partial: Self::Partial) -> Self {
+        Self {
+            enabled_tables: partial.enabled_tables.unwrap_or(self.enabled_tables),
+            min_chunk_size: partial.min_chunk_size.unwrap_or(self.min_chunk_size),
+            max_chunk_size: partial.max_chunk_size.unwrap_or(self.max_chunk_size),
+            target_chunk_size: partial.target_chunk_size.unwrap_or(self.target_chunk_size),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PartialVectorSearchConfig {
+    pub m: Option<usize>,
+    pub ef_construction: Option<usize>,
+    pub ef: Option<usize>,
+    pub dimension: Option<usize>,
+    pub distance_metric: Option<DistanceMetric>,
+}
+
+impl Merge for VectorSearchConfig {
+    type Partial = PartialVectorSearchConfig;
+    fn merge(self, partial: Self::Partial) -> Self {
+        Self {
+            m: partial.m.unwrap_or(self.m),
+            ef_construction: partial.ef_construction.unwrap_or(self.ef_construction),
+            ef: partial.ef.unwrap_or(self.ef),
+            dimension: partial.dimension.unwrap_or(self.dimension),
+            distance_metric: partial.distance_metric.unwrap_or(self.distance_metric),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PartialStreamingConfig {
+    pub redpanda_brokers: Option<Vec<String>>,
+    pub kafka_compatibility: Option<bool>,
+    pub max_message_size: Option<usize>,
+    pub retention_ms: Option<i64>,
+}
+
+impl Merge for StreamingConfig {
+    type Partial = PartialStreamingConfig;
+    fn merge(self, partial: Self::Partial) -> Self {
+        Self {
+            redpanda_brokers: partial.redpanda_brokers.unwrap_or(self.redpanda_brokers),
+            kafka_compatibility: partial.kafka_compatibility.unwrap_or(self.kafka_compatibility),
+            max_message_size: partial.max_message_size.unwrap_or(self.max_message_size),
+            retention_ms: partial.retention_ms.unwrap_or(self.retention_ms),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PartialAIConfig {
+    pub ollama_url: Option<String>,
+    pub embedding_model: Option<String>,
+    pub text_model: Option<String>,
+    pub max_tokens: Option<usize>,
+    pub temperature: Option<f32>,
+    pub embedding_provider: Option<EmbeddingProviderKind>,
+    pub openai_base_url: Option<String>,
+    pub openai_api_key: Option<String>,
+    pub onnx_model_path: Option<String>,
+    pub embedding_queue_debounce_ms: Option<u64>,
+    pub embedding_chunk_size: Option<usize>,
+    pub embedding_chunk_overlap: Option<usize>,
+    pub model_health_probe_interval_ms: Option<u64>,
+    pub model_health_ready_timeout_ms: Option<u64>,
+    pub embedding_cache_max_entries: Option<usize>,
+    pub embedding_cache_max_memory_mb: Option<usize>,
+}
+
+impl Merge for AIConfig {
+    type Partial = PartialAIConfig;
+    fn merge(self, partial: Self::Partial) -> Self {
+        Self {
+            ollama_url: partial.ollama_url.unwrap_or(self.ollama_url),
+            embedding_model: partial.embedding_model.unwrap_or(self.embedding_model),
+            text_model: partial.text_model.unwrap_or(self.text_model),
+            max_tokens: partial.max_tokens.unwrap_or(self.max_tokens),
+            temperature: partial.temperature.unwrap_or(self.temperature),
+            embedding_provider: partial.embedding_provider.unwrap_or(self.embedding_provider),
+            openai_base_url: partial.openai_base_url.unwrap_or(self.openai_base_url),
+            openai_api_key: partial.openai_api_key.or(self.openai_api_key),
+            onnx_model_path: partial.onnx_model_path.or(self.onnx_model_path),
+            embedding_queue_debounce_ms: partial.embedding_queue_debounce_ms.unwrap_or(self.embedding_queue_debounce_ms),
+            embedding_chunk_size: partial.embedding_chunk_size.unwrap_or(self.embedding_chunk_size),
+            embedding_chunk_overlap: partial.embedding_chunk_overlap.unwrap_or(self.embedding_chunk_overlap),
+            model_health_probe_interval_ms: partial.model_health_probe_interval_ms.unwrap_or(self.model_health_probe_interval_ms),
+            model_health_ready_timeout_ms: partial.model_health_ready_timeout_ms.unwrap_or(self.model_health_ready_timeout_ms),
+            embedding_cache_max_entries: partial.embedding_cache_max_entries.unwrap_or(self.embedding_cache_max_entries),
+            embedding_cache_max_memory_mb: partial.embedding_cache_max_memory_mb.unwrap_or(self.embedding_cache_max_memory_mb),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PartialLoggingConfig {
+    pub level: Option<String>,
+    pub format: Option<LogFormat>,
+    pub output: Option<LogOutput>,
+}
+
+impl Merge for LoggingConfig {
+    type Partial = PartialLoggingConfig;
+    fn merge(self, partial: Self::Partial) -> Self {
+        Self {
+            level: partial.level.unwrap_or(self.level),
+            format: partial.format.unwrap_or(self.format),
+            output: partial.output.unwrap_or(self.output),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PartialOtlpConfig {
+    pub enabled: Option<bool>,
+    pub endpoint: Option<String>,
+    pub push_interval_secs: Option<u64>,
+    pub service_name: Option<String>,
+    pub instance_id: Option<String>,
+}
+
+impl Merge for OtlpConfig {
+    type Partial = PartialOtlpConfig;
+    fn merge(self, partial: Self::Partial) -> Self {
+        Self {
+            enabled: partial.enabled.unwrap_or(self.enabled),
+            endpoint: partial.endpoint.unwrap_or(self.endpoint),
+            push_interval_secs: partial.push_interval_secs.unwrap_or(self.push_interval_secs),
+            service_name: partial.service_name.unwrap_or(self.service_name),
+            instance_id: partial.instance_id.unwrap_or(self.instance_id),
         }
     }
 }
 
 impl Config {
+    /// Folds layers in priority order - each later layer only overrides the
+    /// fields it explicitly sets, via `Merge`, so e.g. an env var set
+    /// without a matching config file entry is never lost, and a config
+    /// file entry without a matching env var survives untouched.
     pub fn load() -> Result<Self> {
-        // Try to load from environment variables first
-        let mut config = Self::from_env()?;
-        
-        // Try to load from config file
-        if let Ok(file_config) = Self::from_file() {
-            config = config.merge(file_config);
-        }
-        
-        // Validate configuration
-        config.validate()?;
-        
+        let mut config = Config::default();
+        config.profile = Self::active_profile();
+        let mut origin: Option<PathBuf> = None;
+
+        if let Ok(file_overrides) = Self::from_file() {
+            origin = Some(file_overrides.path.clone());
+            config = config.merge(file_overrides.value);
+        }
+
+        config = config.merge(Self::from_env()?);
+
+        config.validate_at(origin.as_deref())?;
+
         Ok(config)
     }
-    
-    pub fn from_env() -> Result<Self> {
-        let mut config = Config::default();
-        
-        // Server config
+
+    /// Reads overrides from `VECTRA_*` environment variables. Only fields
+    /// whose variable is actually set are populated - everything else is
+    /// `None`, so `Config::merge` leaves earlier layers untouched for them.
+    pub fn from_env() -> Result<PartialConfig> {
+        let mut server = PartialServerConfig::default();
         if let Ok(host) = env::var("VECTRA_HOST") {
-            config.server.host = host;
+            server.host = Some(host);
         }
         if let Ok(port) = env::var("VECTRA_PORT") {
-            config.server.port = port.parse()?;
+            server.port = Some(port.parse()?);
         }
         if let Ok(workers) = env::var("VECTRA_WORKERS") {
-            config.server.workers = workers.parse()?;
+            server.workers = Some(workers.parse()?);
         }
-        
-        // Storage config
+
+        let mut storage = PartialStorageConfig::default();
         if let Ok(data_dir) = env::var("VECTRA_DATA_DIR") {
-            config.storage.data_dir = data_dir;
+            storage.data_dir = Some(data_dir);
         }
         if let Ok(rocksdb_path) = env::var("VECTRA_ROCKSDB_PATH") {
-            config.storage.rocksdb_path = Some(rocksdb_path);
+            storage.rocksdb_path = Some(rocksdb_path);
         }
         if let Ok(sled_path) = env::var("VECTRA_SLED_PATH") {
-            config.storage.sled_path = Some(sled_path);
+            storage.sled_path = Some(sled_path);
         }
-        
-        // Vector search config
+
+        let mut vector_search = PartialVectorSearchConfig::default();
         if let Ok(dimension) = env::var("VECTRA_VECTOR_DIMENSION") {
-            config.vector_search.dimension = dimension.parse()?;
+            vector_search.dimension = Some(dimension.parse()?);
         }
         if let Ok(m) = env::var("VECTRA_HNSW_M") {
-            config.vector_search.m = m.parse()?;
+            vector_search.m = Some(m.parse()?);
         }
-        
-        // Streaming config
+
+        let mut streaming = PartialStreamingConfig::default();
         if let Ok(brokers) = env::var("VECTRA_REDPANDA_BROKERS") {
-            config.streaming.redpanda_brokers = brokers.split(',').map(|s| s.trim().to_string()).collect();
+            streaming.redpanda_brokers = Some(brokers.split(',').map(|s| s.trim().to_string()).collect());
         }
-        
-        // AI config
+
+        let mut ai = PartialAIConfig::default();
         if let Ok(ollama_url) = env::var("VECTRA_OLLAMA_URL") {
-            config.ai.ollama_url = ollama_url;
+            ai.ollama_url = Some(ollama_url);
         }
         if let Ok(embedding_model) = env::var("VECTRA_EMBEDDING_MODEL") {
-            config.ai.embedding_model = embedding_model;
+            ai.embedding_model = Some(embedding_model);
+        }
+        if let Ok(embedding_provider) = env::var("VECTRA_EMBEDDING_PROVIDER") {
+            ai.embedding_provider = Some(match embedding_provider.to_lowercase().as_str() {
+                "onnx" => EmbeddingProviderKind::Onnx,
+                "openai" | "openai_compatible" => EmbeddingProviderKind::OpenAiCompatible,
+                _ => EmbeddingProviderKind::Ollama,
+            });
+        }
+        if let Ok(openai_api_key) = env::var("VECTRA_OPENAI_API_KEY") {
+            ai.openai_api_key = Some(openai_api_key);
+        }
+        if let Ok(onnx_model_path) = env::var("VECTRA_ONNX_MODEL_PATH") {
+            ai.onnx_model_path = Some(onnx_model_path);
+        }
+        if let Ok(debounce_ms) = env::var("VECTRA_EMBEDDING_QUEUE_DEBOUNCE_MS") {
+            ai.embedding_queue_debounce_ms = Some(debounce_ms.parse()?);
+        }
+        if let Ok(chunk_size) = env::var("VECTRA_EMBEDDING_CHUNK_SIZE") {
+            ai.embedding_chunk_size = Some(chunk_size.parse()?);
+        }
+        if let Ok(chunk_overlap) = env::var("VECTRA_EMBEDDING_CHUNK_OVERLAP") {
+            ai.embedding_chunk_overlap = Some(chunk_overlap.parse()?);
+        }
+        if let Ok(probe_interval_ms) = env::var("VECTRA_MODEL_HEALTH_PROBE_INTERVAL_MS") {
+            ai.model_health_probe_interval_ms = Some(probe_interval_ms.parse()?);
+        }
+        if let Ok(ready_timeout_ms) = env::var("VECTRA_MODEL_HEALTH_READY_TIMEOUT_MS") {
+            ai.model_health_ready_timeout_ms = Some(ready_timeout_ms.parse()?);
         }
-        
-        // Logging config
+        if let Ok(max_entries) = env::var("VECTRA_EMBEDDING_CACHE_MAX_ENTRIES") {
+            ai.embedding_cache_max_entries = Some(max_entries.parse()?);
+        }
+        if let Ok(max_memory_mb) = env::var("VECTRA_EMBEDDING_CACHE_MAX_MEMORY_MB") {
+            ai.embedding_cache_max_memory_mb = Some(max_memory_mb.parse()?);
+        }
+
+        let mut logging = PartialLoggingConfig::default();
         if let Ok(level) = env::var("VECTRA_LOG_LEVEL") {
-            config.logging.level = level;
+            logging.level = Some(level);
         }
-        
-        Ok(config)
+
+        let mut otlp = PartialOtlpConfig::default();
+        if let Ok(endpoint) = env::var("VECTRA_OTLP_ENDPOINT") {
+            otlp.endpoint = Some(endpoint);
+            otlp.enabled = Some(true);
+        }
+        if let Ok(push_interval_secs) = env::var("VECTRA_OTLP_PUSH_INTERVAL_SECS") {
+            otlp.push_interval_secs = Some(push_interval_secs.parse()?);
+        }
+        if let Ok(service_name) = env::var("VECTRA_OTLP_SERVICE_NAME") {
+            otlp.service_name = Some(service_name);
+        }
+
+        Ok(PartialConfig {
+            server: Some(server),
+            storage: Some(storage),
+            vector_search: Some(vector_search),
+            streaming: Some(streaming),
+            ai: Some(ai),
+            logging: Some(logging),
+            otlp: Some(otlp),
+        })
     }
-    
-    pub fn from_file() -> Result<Self> {
-        let config_paths = vec![
-            "./config/vectra.toml",
-            "./config/vectra.yaml",
-            "./config/vectra.json",
-            "./vectra.toml",
-            "./vectra.yaml",
-            "./vectra.json",
-        ];
-        
-        for path in config_paths {
-            if let Ok(config) = Self::load_from_file(path) {
-                return Ok(config);
+
+    /// Parses overrides out of the resolved config file (`resolved_path`),
+    /// tagged with the absolute path it came from.
+    pub fn from_file() -> Result<WithPath<PartialConfig>> {
+        let path = Self::resolved_path().ok_or_else(|| anyhow::anyhow!("No configuration file found"))?;
+        let value = Self::parse_partial_file(&path)?;
+        Ok(WithPath { value, path })
+    }
+
+    /// Climbs from the current working directory up to the filesystem
+    /// root - the same search `.git`/`Cargo.toml` discovery uses - looking
+    /// at each level for `config/vectra.{toml,yaml,json}` then bare
+    /// `vectra.{toml,yaml,json}`, and returns the first match as an
+    /// absolute path. This is the single file `from_file` loads and the one
+    /// `ConfigWatcher` watches for hot-reload, so the two always agree on
+    /// which file is authoritative, even when the server is started from a
+    /// nested working directory.
+    pub fn resolved_path() -> Option<PathBuf> {
+        let cwd = std::env::current_dir().ok()?;
+        let mut dir = cwd.as_path();
+
+        loop {
+            for filename in CONFIG_FILENAMES {
+                let candidate = dir.join("config").join(filename);
+                if candidate.is_file() {
+                    return Some(candidate.canonicalize().unwrap_or(candidate));
+                }
+            }
+            for filename in CONFIG_FILENAMES {
+                let candidate = dir.join(filename);
+                if candidate.is_file() {
+                    return Some(candidate.canonicalize().unwrap_or(candidate));
+                }
             }
+
+            dir = dir.parent()?;
         }
-        
-        Err(anyhow::anyhow!("No configuration file found"))
     }
-    
-    fn load_from_file(path: &str) -> Result<Self> {
-        let path_buf = PathBuf::from(path);
-        let extension = path_buf.extension()
+
+    /// Loads `path` as a full, standalone config: `Config::default()`, then
+    /// the file's settings, then `VECTRA_*` env overrides. Used by
+    /// `ConfigWatcher` to reload the live config wholesale from the same
+    /// file `from_file` resolved at startup.
+    pub(crate) fn load_from_file(path: &str) -> Result<Self> {
+        let partial = Self::parse_partial_file(&PathBuf::from(path))?;
+        // Layer env over the file, matching `load()`: a reload triggered by
+        // an unrelated file edit must not revert fields that were only ever
+        // set via a `VECTRA_*` env var back to their defaults.
+        let mut config = Config::default().merge(partial).merge(Self::from_env()?);
+        config.profile = Self::active_profile();
+        Ok(config)
+    }
+
+    fn default_profile() -> String {
+        "default".to_string()
+    }
+
+    /// Active profile name, from `VECTRA_PROFILE`, defaulting to
+    /// `"default"`. A top-level table in the config file matching this name
+    /// (e.g. `[prod]`, containing `[prod.server]`/`[prod.ai]`/...) is
+    /// layered over the base sections - see `layer_profiles`.
+    pub fn active_profile() -> String {
+        env::var("VECTRA_PROFILE").unwrap_or_else(|_| Self::default_profile())
+    }
+
+    fn parse_partial_file(path: &std::path::Path) -> Result<PartialConfig> {
+        let extension = path.extension()
             .and_then(|ext| ext.to_str())
             .unwrap_or("toml");
-        
+
         let content = std::fs::read_to_string(path)?;
-        
-        match extension {
-            "toml" => Ok(toml::from_str(&content)?),
-            "yaml" | "yml" => Ok(serde_yaml::from_str(&content)?),
-            "json" => Ok(serde_json::from_str(&content)?),
-            _ => Err(anyhow::anyhow!("Unsupported config file format: {}", extension)),
+
+        let raw: serde_json::Value = match extension {
+            "toml" => serde_json::to_value(content.parse::<toml::Value>()?)?,
+            "yaml" | "yml" => serde_json::to_value(serde_yaml::from_str::<serde_yaml::Value>(&content)?)?,
+            "json" => serde_json::from_str(&content)?,
+            _ => return Err(anyhow::anyhow!("Unsupported config file format: {}", extension)),
+        };
+
+        Self::layer_profiles(raw, &Self::active_profile())
+    }
+
+    /// Layers the table named `profile` (if present) over the known config
+    /// sections, then drops every other top-level table - those are
+    /// profiles that aren't currently active. This lets one file carry
+    /// `[prod.server]`/`[staging.ai]`/... alongside the base `[server]`/
+    /// `[ai]`/... tables, with only the selected profile's overrides
+    /// applied.
+    fn layer_profiles(raw: serde_json::Value, profile: &str) -> Result<PartialConfig> {
+        let serde_json::Value::Object(mut map) = raw else {
+            return Ok(serde_json::from_value(raw)?);
+        };
+
+        let profile_overrides = map.remove(profile);
+        map.retain(|key, _| CONFIG_SECTION_KEYS.contains(&key.as_str()));
+
+        if let Some(profile_overrides) = profile_overrides {
+            Self::json_merge(&mut map, profile_overrides);
         }
+
+        Ok(serde_json::from_value(serde_json::Value::Object(map))?)
     }
-    
-    pub fn merge(self, other: Self) -> Self {
-        // This is a simple merge strategy - in production you might want more sophisticated merging
-        Self {
-            server: other.server,
-            storage: other.storage,
-            vector_search: other.vector_search,
-            streaming: other.streaming,
-            ai: other.ai,
-            logging: other.logging,
+
+    /// Recursively merges `overlay` into `base`, overriding only the keys
+    /// `overlay` actually sets - the JSON-level equivalent of `Merge`, used
+    /// to fold a profile table's sections into the base sections before
+    /// deserializing the result into `PartialConfig`.
+    fn json_merge(base: &mut serde_json::Map<String, serde_json::Value>, overlay: serde_json::Value) {
+        let serde_json::Value::Object(overlay_map) = overlay else { return };
+
+        for (key, overlay_value) in overlay_map {
+            match (base.get_mut(&key), overlay_value) {
+                (Some(serde_json::Value::Object(base_obj)), serde_json::Value::Object(overlay_obj)) => {
+                    Self::json_merge(base_obj, serde_json::Value::Object(overlay_obj));
+                }
+                (_, overlay_value) => {
+                    base.insert(key, overlay_value);
+                }
+            }
         }
     }
-    
+
+    /// Validates with no known origin file, so error messages are plain
+    /// (e.g. programmatically-built configs in tests). Prefer
+    /// `validate_at` wherever the config's source file is known.
     pub fn validate(&self) -> Result<()> {
+        self.validate_at(None)
+    }
+
+    /// Validates the config, annotating every error with `origin` (the
+    /// absolute path the config was loaded from) when given, so operators
+    /// running from a nested working directory get an actionable message
+    /// like "invalid port in /home/u/project/vectra.toml" instead of
+    /// having to guess which file is live.
+    pub fn validate_at(&self, origin: Option<&std::path::Path>) -> Result<()> {
+        let suffix = origin.map(|path| format!(" in {}", path.display())).unwrap_or_default();
+
         // Validate server config
         if self.server.port == 0 {
-            return Err(anyhow::anyhow!("Invalid port number"));
+            return Err(anyhow::anyhow!("Invalid port number{}", suffix));
         }
-        
+
         // Validate storage config
         if let Some(ref path) = self.storage.rocksdb_path {
             let path_buf = PathBuf::from(path);
@@ -279,17 +954,40 @@ impl Config {
                 }
             }
         }
-        
+
         // Validate vector search config
         if self.vector_search.dimension == 0 {
-            return Err(anyhow::anyhow!("Vector dimension must be greater than 0"));
+            return Err(anyhow::anyhow!("Vector dimension must be greater than 0{}", suffix));
         }
-        
+
         // Validate AI config
         if self.ai.temperature < 0.0 || self.ai.temperature > 2.0 {
-            return Err(anyhow::anyhow!("Temperature must be between 0.0 and 2.0"));
+            return Err(anyhow::anyhow!("Temperature must be between 0.0 and 2.0{}", suffix));
+        }
+        if self.ai.embedding_chunk_overlap >= self.ai.embedding_chunk_size {
+            return Err(anyhow::anyhow!("embedding_chunk_overlap must be smaller than embedding_chunk_size{}", suffix));
+        }
+        if self.ai.model_health_probe_interval_ms == 0 {
+            return Err(anyhow::anyhow!("model_health_probe_interval_ms must be greater than 0{}", suffix));
+        }
+        if self.ai.embedding_cache_max_entries == 0 {
+            return Err(anyhow::anyhow!("embedding_cache_max_entries must be greater than 0{}", suffix));
+        }
+
+        // Validate OTLP config
+        if self.otlp.enabled && self.otlp.push_interval_secs == 0 {
+            return Err(anyhow::anyhow!("OTLP push interval must be greater than 0{}", suffix));
+        }
+
+        // Validate logging config. `distance_metric`/`format` can't be
+        // invalid by construction - unknown strings already fail to
+        // deserialize - but `LogOutput::File` still needs a non-empty path.
+        if let LogOutput::File(ref path) = self.logging.output {
+            if path.as_os_str().is_empty() {
+                return Err(anyhow::anyhow!("logging.output file path must not be empty{}", suffix));
+            }
         }
-        
+
         Ok(())
     }
     
@@ -304,6 +1002,51 @@ impl Config {
     pub fn to_json(&self) -> Result<String> {
         Ok(serde_json::to_string_pretty(self)?)
     }
+
+    /// Writes this config to `path` durably: serialize by `path`'s
+    /// extension, write to a sibling `<path>.tmp` created with
+    /// `create_new(true)` (never clobbers a stale temp file left by a
+    /// previous crash) and, on Unix, mode `0o600` since config files can
+    /// hold API keys, `sync_data()` it, then `rename` the temp file over
+    /// `path`. A crash at any point before the rename leaves the original
+    /// file untouched; the temp file is removed on any failure along the
+    /// way rather than left behind.
+    pub fn save_to_file(&self, path: &std::path::Path) -> Result<()> {
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("toml");
+        let content = match extension {
+            "toml" => self.to_toml()?,
+            "yaml" | "yml" => self.to_yaml()?,
+            "json" => self.to_json()?,
+            _ => return Err(anyhow::anyhow!("Unsupported config file format: {}", extension)),
+        };
+
+        let tmp_path = path.with_extension(format!("{}.tmp", extension));
+
+        let result = (|| -> Result<()> {
+            let mut options = std::fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            let mut file = options.open(&tmp_path)?;
+
+            use std::io::Write;
+            file.write_all(content.as_bytes())?;
+            file.sync_data()?;
+            drop(file);
+
+            std::fs::rename(&tmp_path, path)?;
+            Ok(())
+        })();
+
+        if result.is_err() {
+            let _ = std::fs::remove_file(&tmp_path);
+        }
+
+        result
+    }
 }
 
 #[cfg(test)]
@@ -316,13 +1059,161 @@ mod tests {
         assert_eq!(config.server.port, 8080);
         assert_eq!(config.vector_search.dimension, 384);
         assert_eq!(config.ai.temperature, 0.7);
+        assert!(!config.otlp.enabled);
+    }
+
+    #[test]
+    fn test_otlp_validation_requires_positive_push_interval_when_enabled() {
+        let mut config = Config::default();
+        config.otlp.enabled = true;
+        config.otlp.push_interval_secs = 0;
+
+        assert!(config.validate().is_err());
     }
     
     #[test]
     fn test_config_validation() {
         let mut config = Config::default();
         config.server.port = 0;
-        
+
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_merge_only_overrides_explicitly_set_fields() {
+        let base = Config::default();
+
+        let mut file_overrides = PartialConfig::default();
+        file_overrides.server = Some(PartialServerConfig {
+            host: Some("0.0.0.0".to_string()),
+            ..Default::default()
+        });
+        let after_file = base.merge(file_overrides);
+        assert_eq!(after_file.server.host, "0.0.0.0");
+        // Fields the file didn't mention keep the default.
+        assert_eq!(after_file.server.port, 8080);
+
+        let mut env_overrides = PartialConfig::default();
+        env_overrides.server = Some(PartialServerConfig {
+            port: Some(9000),
+            ..Default::default()
+        });
+        let after_env = after_file.merge(env_overrides);
+        // The env layer only set `port`, so the file's `host` override survives.
+        assert_eq!(after_env.server.host, "0.0.0.0");
+        assert_eq!(after_env.server.port, 9000);
+    }
+
+    #[test]
+    fn test_validate_at_includes_origin_path_in_error() {
+        let mut config = Config::default();
+        config.server.port = 0;
+
+        let origin = std::path::Path::new("/home/u/project/vectra.toml");
+        let err = config.validate_at(Some(origin)).unwrap_err();
+        assert!(err.to_string().contains("/home/u/project/vectra.toml"));
+    }
+
+    #[test]
+    fn test_save_to_file_writes_readable_config_and_cleans_up_tmp() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vectra.toml");
+
+        let config = Config::default();
+        config.save_to_file(&path).unwrap();
+
+        assert!(path.is_file());
+        assert!(!path.with_extension("toml.tmp").exists());
+
+        let reloaded = Config::load_from_file(&path.to_string_lossy()).unwrap();
+        assert_eq!(reloaded.server.port, config.server.port);
+    }
+
+    #[test]
+    fn test_save_to_file_overwrites_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vectra.toml");
+        std::fs::write(&path, "stale content").unwrap();
+
+        let mut config = Config::default();
+        config.server.port = 9999;
+        config.save_to_file(&path).unwrap();
+
+        let reloaded = Config::load_from_file(&path.to_string_lossy()).unwrap();
+        assert_eq!(reloaded.server.port, 9999);
+    }
+
+    #[test]
+    fn test_load_from_file_keeps_env_sourced_fields_on_reload() {
+        // `load_from_file` backs `ConfigWatcher`'s hot-reload: an edit to an
+        // unrelated field must not revert a field that was only ever set
+        // via a `VECTRA_*` env var back to its default. The config file
+        // below never mentions `otlp`, mirroring a real user file that
+        // doesn't set every field - unlike a `save_to_file` round-trip,
+        // which would write every field explicitly and mask this bug.
+        env::set_var("VECTRA_OTLP_SERVICE_NAME", "env-sourced-service");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vectra.toml");
+        std::fs::write(&path, "[server]\nport = 9001\n").unwrap();
+
+        let reloaded = Config::load_from_file(&path.to_string_lossy()).unwrap();
+
+        env::remove_var("VECTRA_OTLP_SERVICE_NAME");
+
+        assert_eq!(reloaded.server.port, 9001);
+        assert_eq!(reloaded.otlp.service_name, "env-sourced-service");
+    }
+
+    #[test]
+    fn test_layer_profiles_applies_only_the_selected_profile_table() {
+        let raw = serde_json::json!({
+            "server": { "host": "0.0.0.0", "port": 8080 },
+            "ai": { "temperature": 0.7 },
+            "prod": {
+                "server": { "max_connections": 5000 },
+                "ai": { "temperature": 0.1 }
+            },
+            "staging": {
+                "ai": { "temperature": 0.5 }
+            }
+        });
+
+        let default_partial = Config::layer_profiles(raw.clone(), "default").unwrap();
+        assert_eq!(default_partial.ai.unwrap().temperature, Some(0.7));
+
+        let prod_partial = Config::layer_profiles(raw.clone(), "prod").unwrap();
+        let prod_server = prod_partial.server.unwrap();
+        assert_eq!(prod_server.host, Some("0.0.0.0".to_string()));
+        assert_eq!(prod_server.max_connections, Some(5000));
+        assert_eq!(prod_partial.ai.unwrap().temperature, Some(0.1));
+
+        let staging_partial = Config::layer_profiles(raw, "staging").unwrap();
+        assert_eq!(staging_partial.ai.unwrap().temperature, Some(0.5));
+    }
+
+    #[test]
+    fn test_distance_metric_parses_case_insensitively_and_rejects_unknown() {
+        assert_eq!("Cosine".parse::<DistanceMetric>().unwrap(), DistanceMetric::Cosine);
+        assert_eq!("DOT_PRODUCT".parse::<DistanceMetric>().unwrap(), DistanceMetric::DotProduct);
+        assert_eq!("dotproduct".parse::<DistanceMetric>().unwrap(), DistanceMetric::DotProduct);
+
+        let err = "cosein".parse::<DistanceMetric>().unwrap_err();
+        assert!(err.contains("cosine"), "error should list accepted variants: {}", err);
+    }
+
+    #[test]
+    fn test_log_output_treats_non_stream_strings_as_file_paths() {
+        let stdout: LogOutput = serde_json::from_str("\"STDOUT\"").unwrap();
+        assert_eq!(stdout, LogOutput::Stdout);
+
+        let file: LogOutput = serde_json::from_str("\"/var/log/vectra.log\"").unwrap();
+        assert_eq!(file, LogOutput::File(PathBuf::from("/var/log/vectra.log")));
+    }
+
+    #[test]
+    fn test_unknown_distance_metric_fails_to_deserialize_with_clear_message() {
+        let err = serde_json::from_str::<DistanceMetric>("\"cosein\"").unwrap_err();
+        assert!(err.to_string().contains("cosine"));
+    }
 }
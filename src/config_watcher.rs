@@ -0,0 +1,147 @@
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::config::Config;
+
+/// Watches the resolved config file (`Config::resolved_path`) and hot-reloads
+/// `Config` at runtime without a restart. Each valid reload is published on
+/// the returned `watch::Receiver`, which subsystems can subscribe to in
+/// order to re-apply whatever changed instead of restarting.
+pub struct ConfigWatcher;
+
+impl ConfigWatcher {
+    /// Spawns the watch loop as a background thread and returns a receiver
+    /// seeded with `initial`. A no-op (the receiver never updates) when no
+    /// config file was resolved, since there's nothing on disk to watch.
+    pub fn spawn(initial: Config) -> watch::Receiver<Config> {
+        let (tx, rx) = watch::channel(initial);
+
+        let Some(path) = Config::resolved_path() else {
+            tracing::debug!("no config file resolved on disk; hot-reload disabled");
+            return rx;
+        };
+
+        std::thread::spawn(move || Self::watch_loop(path, tx));
+
+        rx
+    }
+
+    /// Blocking watch loop, run on its own thread since `notify`'s callback
+    /// and the debounce wait below are synchronous. Debounces bursts of
+    /// filesystem events (editors and atomic-save tooling typically emit
+    /// several writes per save) before attempting a reload, and only swaps
+    /// in a config that passes `validate()` - an invalid edit is logged and
+    /// the last-good config stays live.
+    fn watch_loop(path: PathBuf, tx: watch::Sender<Config>) {
+        const DEBOUNCE: Duration = Duration::from_millis(250);
+
+        let (raw_tx, raw_rx) = std::sync::mpsc::channel();
+        let mut watcher = match notify::recommended_watcher(move |event| {
+            let _ = raw_tx.send(event);
+        }) {
+            Ok(watcher) => watcher,
+            Err(err) => {
+                tracing::warn!("failed to start config watcher for {}: {}", path.display(), err);
+                return;
+            }
+        };
+
+        if let Err(err) = notify::Watcher::watch(&mut watcher, &path, notify::RecursiveMode::NonRecursive) {
+            tracing::warn!("failed to watch {}: {}", path.display(), err);
+            return;
+        }
+
+        loop {
+            let Ok(event) = raw_rx.recv() else { return };
+            if let Err(err) = event {
+                tracing::warn!("config watcher for {} reported an error: {}", path.display(), err);
+                continue;
+            }
+            // Drain anything else that lands within the debounce window so
+            // a burst of writes for one save reloads exactly once.
+            while raw_rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+            match Config::load_from_file(&path.to_string_lossy()) {
+                Ok(reloaded) => {
+                    if let Err(err) = reloaded.validate_at(Some(&path)) {
+                        tracing::warn!("reloaded config failed validation, keeping previous config live: {}", err);
+                        continue;
+                    }
+
+                    let previous = tx.send_replace(reloaded.clone());
+                    let changed = diff_paths(&previous, &reloaded);
+                    if changed.is_empty() {
+                        tracing::info!("config reloaded from {} with no effective changes", path.display());
+                    } else {
+                        tracing::info!(
+                            "config reloaded from {}; changed fields: {}",
+                            path.display(),
+                            changed.join(", ")
+                        );
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!("failed to reload config from {}: {}", path.display(), err);
+                }
+            }
+        }
+    }
+}
+
+/// Dotted paths (e.g. `ai.temperature`, `logging.level`) of every leaf value
+/// that differs between `before` and `after`, so the reload log line shows
+/// which section - and which field within it - actually changed.
+fn diff_paths(before: &Config, after: &Config) -> Vec<String> {
+    let before = serde_json::to_value(before).unwrap_or(serde_json::Value::Null);
+    let after = serde_json::to_value(after).unwrap_or(serde_json::Value::Null);
+
+    let mut paths = Vec::new();
+    collect_diff_paths(&before, &after, "", &mut paths);
+    paths
+}
+
+fn collect_diff_paths(before: &serde_json::Value, after: &serde_json::Value, prefix: &str, out: &mut Vec<String>) {
+    match (before, after) {
+        (serde_json::Value::Object(before_map), serde_json::Value::Object(after_map)) => {
+            let mut keys: Vec<&String> = before_map.keys().chain(after_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                match (before_map.get(key), after_map.get(key)) {
+                    (Some(b), Some(a)) => collect_diff_paths(b, a, &path, out),
+                    _ => out.push(path),
+                }
+            }
+        }
+        _ => {
+            if before != after {
+                out.push(prefix.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_paths_reports_changed_leaf_fields() {
+        let mut before = Config::default();
+        let mut after = before.clone();
+        after.ai.temperature = 0.1;
+        after.logging.level = "debug".to_string();
+
+        let changed = diff_paths(&before, &after);
+        assert!(changed.contains(&"ai.temperature".to_string()));
+        assert!(changed.contains(&"logging.level".to_string()));
+        assert_eq!(changed.len(), 2);
+
+        before.ai.temperature = 0.1;
+        before.logging.level = "debug".to_string();
+        assert!(diff_paths(&before, &after).is_empty());
+    }
+}
@@ -0,0 +1,343 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::config::{AIConfig, EmbeddingProviderKind};
+
+/// Common interface implemented by every embedding backend `AIRuntime` can be
+/// configured to use, the embedding-model counterpart to
+/// `crate::storage_backend::StorageBackend`.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+    fn dimensions(&self) -> usize;
+    fn max_input_tokens(&self) -> usize;
+}
+
+/// Errors callers may want to match on, as opposed to the generic
+/// `anyhow::Error` used for "can't happen in practice" failures. In
+/// particular, `crate::queue::EmbeddingQueue` downcasts to this to decide
+/// whether a failed batch should be retried.
+#[derive(Debug, Error)]
+pub enum EmbeddingError {
+    #[error("embedding provider rate-limited the request")]
+    RateLimited {
+        /// Delay requested by the provider's `Retry-After` header, if any.
+        retry_after: Option<Duration>,
+    },
+}
+
+/// Extracts a `Retry-After` delay (seconds form only, as used by every
+/// provider this module talks to) from an HTTP response's headers.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Talks to a local Ollama server's `/api/embeddings` endpoint.
+pub struct OllamaEmbeddingProvider {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    dimensions: usize,
+    max_input_tokens: usize,
+}
+
+impl OllamaEmbeddingProvider {
+    pub fn new(base_url: String, model: String, dimensions: usize, max_input_tokens: usize) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+            model,
+            dimensions,
+            max_input_tokens,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct OllamaEmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddingProvider {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let url = format!("{}/api/embeddings", self.base_url.trim_end_matches('/'));
+        let mut embeddings = Vec::with_capacity(texts.len());
+
+        // Ollama's embeddings endpoint takes one prompt per request.
+        for text in texts {
+            let response = self
+                .client
+                .post(&url)
+                .json(&serde_json::json!({ "model": self.model, "prompt": text }))
+                .send()
+                .await?;
+
+            if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                return Err(EmbeddingError::RateLimited { retry_after: retry_after(&response) }.into());
+            }
+
+            let response = response.error_for_status()?.json::<OllamaEmbeddingResponse>().await?;
+            embeddings.push(response.embedding);
+        }
+
+        Ok(embeddings)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn max_input_tokens(&self) -> usize {
+        self.max_input_tokens
+    }
+}
+
+/// Talks to a remote OpenAI-compatible embeddings API (OpenAI itself, or any
+/// server implementing the same `/embeddings` request/response shape).
+pub struct OpenAiEmbeddingProvider {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+    dimensions: usize,
+    max_input_tokens: usize,
+}
+
+impl OpenAiEmbeddingProvider {
+    pub fn new(
+        base_url: String,
+        api_key: String,
+        model: String,
+        dimensions: usize,
+        max_input_tokens: usize,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+            api_key,
+            model,
+            dimensions,
+            max_input_tokens,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbeddingResponse {
+    data: Vec<OpenAiEmbeddingDatum>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbeddingDatum {
+    embedding: Vec<f32>,
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbeddingProvider {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let url = format!("{}/embeddings", self.base_url.trim_end_matches('/'));
+
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.api_key)
+            .json(&serde_json::json!({ "model": self.model, "input": texts }))
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            return Err(EmbeddingError::RateLimited { retry_after: retry_after(&response) }.into());
+        }
+
+        let response = response.error_for_status()?.json::<OpenAiEmbeddingResponse>().await?;
+
+        Ok(response.data.into_iter().map(|datum| datum.embedding).collect())
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn max_input_tokens(&self) -> usize {
+        self.max_input_tokens
+    }
+}
+
+/// Loads a local ONNX embedding model via ONNX Runtime. `embed` always
+/// returns an error: no tokenizer is wired in to turn texts into the
+/// model's input tensors (see `embed_blocking`). The call is made on a
+/// blocking thread rather than directly on the async runtime.
+pub struct OnnxEmbeddingProvider {
+    session: Arc<ort::Session>,
+    dimensions: usize,
+    max_input_tokens: usize,
+}
+
+impl OnnxEmbeddingProvider {
+    pub fn load(model_path: &str, dimensions: usize, max_input_tokens: usize) -> Result<Self> {
+        let session = ort::Session::builder()?.with_model_from_file(model_path)?;
+        Ok(Self {
+            session: Arc::new(session),
+            dimensions,
+            max_input_tokens,
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OnnxEmbeddingProvider {
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let session = self.session.clone();
+        let texts = texts.to_vec();
+        let dimensions = self.dimensions;
+
+        tokio::task::spawn_blocking(move || Self::embed_blocking(&session, &texts, dimensions))
+            .await?
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn max_input_tokens(&self) -> usize {
+        self.max_input_tokens
+    }
+}
+
+impl OnnxEmbeddingProvider {
+    /// Tokenizes `texts` and runs them through `session` to produce
+    /// embeddings. `ort::Session` only exposes tensor-in/tensor-out
+    /// `run`/`run_with_options` - there's no text-in/vector-out convenience
+    /// call, so getting from `texts` to output vectors needs the model's own
+    /// tokenizer (vocab + special tokens) to build input tensors first, and
+    /// that isn't wired up yet. Fail loudly instead of shipping a call that
+    /// doesn't exist on `ort::Session`.
+    fn embed_blocking(_session: &ort::Session, texts: &[String], _dimensions: usize) -> Result<Vec<Vec<f32>>> {
+        Err(anyhow!(
+            "OnnxEmbeddingProvider::embed is not implemented yet: tokenizing {} input(s) into \
+             model tensors requires a model-specific tokenizer that hasn't been wired up",
+            texts.len()
+        ))
+    }
+}
+
+/// Builds the `EmbeddingProvider` for `model_name`, using `parameters` to
+/// override `defaults` (e.g. a per-model `provider`/`endpoint`/`model_path`
+/// supplied to `AIRuntime::add_model`) where present.
+pub fn build_provider(
+    defaults: &AIConfig,
+    model_name: &str,
+    parameters: &HashMap<String, Value>,
+) -> Result<Box<dyn EmbeddingProvider>> {
+    let dimensions = parameters
+        .get("dimensions")
+        .and_then(Value::as_u64)
+        .map(|d| d as usize)
+        .unwrap_or(384);
+    let max_input_tokens = parameters
+        .get("max_input_tokens")
+        .and_then(Value::as_u64)
+        .map(|t| t as usize)
+        .unwrap_or(8192);
+
+    let kind = match parameters.get("provider").and_then(Value::as_str) {
+        Some("onnx") => EmbeddingProviderKind::Onnx,
+        Some("openai") | Some("openai_compatible") => EmbeddingProviderKind::OpenAiCompatible,
+        Some("ollama") => EmbeddingProviderKind::Ollama,
+        Some(other) => return Err(anyhow!("unknown embedding provider '{}'", other)),
+        None => defaults.embedding_provider.clone(),
+    };
+
+    match kind {
+        EmbeddingProviderKind::Ollama => {
+            let base_url = parameters
+                .get("endpoint")
+                .and_then(Value::as_str)
+                .unwrap_or(&defaults.ollama_url)
+                .to_string();
+            Ok(Box::new(OllamaEmbeddingProvider::new(
+                base_url,
+                model_name.to_string(),
+                dimensions,
+                max_input_tokens,
+            )))
+        }
+        EmbeddingProviderKind::OpenAiCompatible => {
+            let base_url = parameters
+                .get("endpoint")
+                .and_then(Value::as_str)
+                .unwrap_or(&defaults.openai_base_url)
+                .to_string();
+            let api_key = parameters
+                .get("api_key")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .or_else(|| defaults.openai_api_key.clone())
+                .ok_or_else(|| anyhow!("openai-compatible embedding provider requires an api_key"))?;
+            Ok(Box::new(OpenAiEmbeddingProvider::new(
+                base_url,
+                api_key,
+                model_name.to_string(),
+                dimensions,
+                max_input_tokens,
+            )))
+        }
+        EmbeddingProviderKind::Onnx => {
+            let model_path = parameters
+                .get("model_path")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .or_else(|| defaults.onnx_model_path.clone())
+                .ok_or_else(|| anyhow!("onnx embedding provider requires a model_path"))?;
+            Ok(Box::new(OnnxEmbeddingProvider::load(&model_path, dimensions, max_input_tokens)?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_provider_defaults_to_ollama() {
+        let config = AIConfig::default();
+        let provider = build_provider(&config, "text-embedding-ada-002", &HashMap::new()).unwrap();
+        assert_eq!(provider.dimensions(), 384);
+    }
+
+    #[test]
+    fn test_build_provider_openai_requires_api_key() {
+        let mut config = AIConfig::default();
+        config.embedding_provider = EmbeddingProviderKind::OpenAiCompatible;
+        config.openai_api_key = None;
+
+        let result = build_provider(&config, "text-embedding-3-small", &HashMap::new());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_build_provider_parameters_override_provider_kind() {
+        let config = AIConfig::default();
+        let mut parameters = HashMap::new();
+        parameters.insert("provider".to_string(), serde_json::json!("openai"));
+        parameters.insert("api_key".to_string(), serde_json::json!("sk-test"));
+
+        let provider = build_provider(&config, "text-embedding-3-small", &parameters).unwrap();
+        assert_eq!(provider.dimensions(), 384);
+    }
+}
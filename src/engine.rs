@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use datafusion::prelude::*;
@@ -6,13 +8,62 @@ use serde_json::Value;
 use anyhow::Result;
 
 use crate::{
-    vector::VectorIndex,
+    bm25,
+    vector::{row_id_from_key, VectorIndex},
     streaming::StreamManager,
     ai::AIRuntime,
     storage::StorageManager,
     config::Config,
+    jobs::{JobManager, JobScheduler, JobSpec, JobState},
 };
 
+/// Constant from the Reciprocal Rank Fusion formula: a document at 1-based
+/// rank `r` contributes `1 / (RRF_K + r)` to its fused score. 60 is the
+/// value RRF's original paper found worked well across rankers.
+const RRF_K: f64 = 60.0;
+
+/// A `VECTOR(n)` column declared (via `create_table`'s DDL) to be populated
+/// automatically from one or more `TEXT` source columns, e.g.
+/// `embedding VECTOR(384) FROM (title+content)`. Opt-in per column, so a
+/// table whose schema omits `FROM (...)` keeps taking manually-supplied
+/// embeddings.
+#[derive(Debug, Clone)]
+struct AutoEmbedColumn {
+    vector_column: String,
+    source_columns: Vec<String>,
+}
+
+/// Scans `schema` (the column-definitions part of a `CREATE TABLE`) for
+/// `<column> VECTOR(n) FROM (<col>+<col>...)` declarations.
+fn parse_autoembed_columns(schema: &str) -> Vec<AutoEmbedColumn> {
+    let mut columns = Vec::new();
+
+    for part in schema.split(',') {
+        let trimmed = part.trim();
+        let lower = trimmed.to_lowercase();
+
+        if !lower.contains("vector(") {
+            continue;
+        }
+        let Some(from_index) = lower.find(" from ") else { continue };
+        let Some(vector_column) = trimmed.split_whitespace().next() else { continue };
+
+        let sources_part = trimmed[from_index + 6..].trim();
+        let sources_part = sources_part.trim_start_matches('(').trim_end_matches(')');
+        let source_columns: Vec<String> = sources_part
+            .split('+')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        if !source_columns.is_empty() {
+            columns.push(AutoEmbedColumn { vector_column: vector_column.to_string(), source_columns });
+        }
+    }
+
+    columns
+}
+
 pub struct VectraEngine {
     config: Config,
     ctx: ExecutionContext,
@@ -20,19 +71,28 @@ pub struct VectraEngine {
     stream_manager: Arc<StreamManager>,
     ai_runtime: Arc<AIRuntime>,
     storage: Arc<StorageManager>,
+    job_manager: Arc<JobManager>,
+    /// Allocates the vector-index id for each chunk `embed_and_index_document`
+    /// inserts, since one source row now fans out into many index entries.
+    next_chunk_id: Arc<AtomicU32>,
+    /// Per-table autoembedding config, keyed by table name - see
+    /// `AutoEmbedColumn` and `parse_autoembed_columns`.
+    autoembed_columns: Arc<RwLock<HashMap<String, Vec<AutoEmbedColumn>>>>,
 }
 
 impl VectraEngine {
     pub async fn new(config: &Config) -> Result<Self> {
         // Initialize DataFusion context
         let ctx = ExecutionContext::new();
-        
+
         // Initialize components
-        let vector_index = Arc::new(VectorIndex::new(&config).await?);
-        let stream_manager = Arc::new(StreamManager::new(&config).await?);
-        let ai_runtime = Arc::new(AIRuntime::new(&config).await?);
         let storage = Arc::new(StorageManager::new(&config).await?);
-        
+        let vector_index = Arc::new(VectorIndex::new(&config).await?);
+        let stream_manager = Arc::new(StreamManager::new(&config, storage.clone()).await?);
+        let ai_runtime = Arc::new(AIRuntime::new(&config, storage.clone()).await?);
+        let job_manager = Arc::new(JobManager::new(storage.clone(), ai_runtime.clone(), vector_index.clone()).await?);
+        JobScheduler::spawn(job_manager.clone());
+
         Ok(Self {
             config: config.clone(),
             ctx,
@@ -40,8 +100,27 @@ impl VectraEngine {
             stream_manager,
             ai_runtime,
             storage,
+            job_manager,
+            next_chunk_id: Arc::new(AtomicU32::new(0)),
+            autoembed_columns: Arc::new(RwLock::new(HashMap::new())),
         })
     }
+
+    pub async fn create_job(&self, spec: JobSpec) -> Result<()> {
+        self.job_manager.create_job(spec).await
+    }
+
+    pub async fn delete_job(&self, name: &str) -> Result<()> {
+        self.job_manager.delete_job(name).await
+    }
+
+    pub async fn list_jobs(&self) -> Vec<JobState> {
+        self.job_manager.list_jobs().await
+    }
+
+    pub async fn run_job_now(&self, name: &str) -> Result<usize> {
+        self.job_manager.run_job_now(name).await
+    }
     
     pub async fn execute_query(&self, sql: &str) -> Result<Value> {
         // Parse and execute SQL query
@@ -54,39 +133,288 @@ impl VectraEngine {
         Ok(json_results)
     }
     
-    pub async fn vector_search(&self, query: &str, limit: usize) -> Result<Vec<Value>> {
+    pub async fn vector_search(
+        &self,
+        table_name: &str,
+        column_name: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<Value>> {
         // Generate embedding for the query
         let embedding = self.ai_runtime.generate_embedding(query).await?;
-        
-        // Perform vector search
-        let results = self.vector_index.search(&embedding, limit).await?;
-        
+
+        // Perform vector search against the requested index
+        let results = self
+            .vector_index
+            .search(table_name, column_name, &embedding, limit)
+            .await?;
+
         Ok(results)
     }
     
+    /// Ranks the `"text"` metadata of indexed chunks (see
+    /// `embed_and_index_document`) against `query` with BM25, independent of
+    /// `vector_search`'s semantic ranking.
+    pub async fn keyword_search(
+        &self,
+        table_name: &str,
+        column_name: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<Value>> {
+        let documents = self.vector_index.text_documents(table_name, column_name).await?;
+
+        let id_text: Vec<(u32, String)> = documents.iter().map(|(id, text, _)| (*id, text.clone())).collect();
+        let metadata_by_id: HashMap<u32, Value> = documents.into_iter().map(|(id, _, metadata)| (id, metadata)).collect();
+
+        let ranked = bm25::search(query, &id_text);
+
+        Ok(ranked
+            .into_iter()
+            .take(limit)
+            .map(|(id, score)| {
+                serde_json::json!({
+                    "id": id,
+                    "score": score,
+                    "metadata": metadata_by_id.get(&id).cloned().unwrap_or(Value::Null),
+                })
+            })
+            .collect())
+    }
+
+    /// Retrieves the top `limit` chunks for `query`, assembles them into a
+    /// numbered context prompt, and asks the text model to answer grounded
+    /// in that context. Returns the retrieved sources alongside the
+    /// generated answer so the caller (the `/rag` SSE stream) can print
+    /// citations back to whichever source backs each claim.
+    pub async fn rag_query(
+        &self,
+        table_name: &str,
+        column_name: &str,
+        query: &str,
+        limit: usize,
+        model: Option<&str>,
+    ) -> Result<(Vec<Value>, String)> {
+        let sources = self.vector_search(table_name, column_name, query, limit).await?;
+
+        let context = sources
+            .iter()
+            .enumerate()
+            .map(|(i, source)| {
+                let text = source["metadata"]["text"].as_str().unwrap_or_default();
+                format!("[{}] {}", i + 1, text)
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let prompt = format!(
+            "Answer the question using only the numbered context below, citing sources as [n].\n\nContext:\n{}\n\nQuestion: {}",
+            context, query
+        );
+
+        let answer = self.ai_runtime.generate_text(&prompt, 512, model).await?;
+        Ok((sources, answer))
+    }
+
+    /// Fuses `vector_search`'s semantic ranking with `keyword_search`'s BM25
+    /// ranking via Reciprocal Rank Fusion, so exact-term and conceptual
+    /// queries both work well in one call. `semantic_ratio` (0.0-1.0) weighs
+    /// each list's contribution to the fused score; 0.5 weighs them evenly.
+    /// Falls back gracefully to whichever list has results when the other is
+    /// empty (e.g. no indexed chunk carries `"text"` metadata yet).
+    pub async fn hybrid_search(
+        &self,
+        table_name: &str,
+        column_name: &str,
+        query: &str,
+        limit: usize,
+        semantic_ratio: f32,
+    ) -> Result<Vec<Value>> {
+        // Pull a deeper pool from each ranker than `limit` before fusing, so
+        // a document that ranks modestly on both sides can still out-fuse
+        // one that ranks #1 on only one side.
+        let fetch_limit = limit.saturating_mul(4).max(limit);
+
+        let semantic = self.vector_search(table_name, column_name, query, fetch_limit).await?;
+        let keyword = self.keyword_search(table_name, column_name, query, fetch_limit).await?;
+
+        Ok(reciprocal_rank_fusion(&semantic, &keyword, semantic_ratio, limit))
+    }
+
+    /// Fetches up to `max` unread messages queued for `subscription_id` -
+    /// see `StreamManager::poll`. Used by the `/stream` WebSocket handler to
+    /// turn the offset-based log into a push stream.
+    pub async fn poll_stream(&self, subscription_id: &str, max: usize) -> Result<Vec<(u64, Value)>> {
+        self.stream_manager.poll(subscription_id, max).await
+    }
+
+    pub async fn commit_stream_offset(&self, subscription_id: &str, offset: u64) -> Result<()> {
+        self.stream_manager.commit_offset(subscription_id, offset).await
+    }
+
+    pub async fn unsubscribe_stream(&self, subscription_id: &str) -> Result<()> {
+        self.stream_manager.unsubscribe(subscription_id).await
+    }
+
     pub async fn subscribe_stream(&self, topic: &str) -> Result<StreamSubscription> {
         let subscription = self.stream_manager.subscribe(topic).await?;
         Ok(subscription)
     }
+
+    /// Pushes the embedding cache's stats into `registry`, alongside
+    /// `QueryCache`/`VectorCache`'s own `export_metrics` calls, so `/metrics`
+    /// also reports the AI runtime's cache effectiveness.
+    pub async fn export_ai_metrics(&self, registry: &crate::metrics::CacheMetricsRegistry) {
+        self.ai_runtime.export_metrics(registry).await;
+    }
     
     pub async fn create_table(&self, table_name: &str, schema: &str) -> Result<()> {
         // Create table using DataFusion
         let create_sql = format!("CREATE TABLE {} ({})", table_name, schema);
         self.ctx.sql(&create_sql).await?;
+
+        self.storage.create_table(table_name, schema, None, None).await?;
+
+        let autoembed_columns = parse_autoembed_columns(schema);
+        if !autoembed_columns.is_empty() {
+            for column in &autoembed_columns {
+                self.vector_index.create_index(table_name, &column.vector_column).await?;
+            }
+            self.autoembed_columns.write().await.insert(table_name.to_string(), autoembed_columns);
+        }
+
         Ok(())
     }
-    
-    pub async fn insert_data(&self, table_name: &str, data: Value) -> Result<()> {
-        // Insert data into table
-        // This would involve converting JSON to Arrow format and inserting
-        Ok(())
+
+    /// Upserts `data` under `key` in `table_name`. For any `VECTOR(n)`
+    /// column the table declared as `FROM (...)` autoembedded (see
+    /// `parse_autoembed_columns`), this also concatenates the declared
+    /// source columns and calls `AIRuntime::generate_embedding` to populate
+    /// that column transparently, skipping the regeneration if none of its
+    /// source columns actually changed from the previously stored row.
+    pub async fn insert_data(&self, table_name: &str, key: &str, data: &Value) -> Result<()> {
+        let mut data = data.clone();
+        let autoembed_columns = self.autoembed_columns.read().await.get(table_name).cloned();
+
+        if let Some(autoembed_columns) = autoembed_columns {
+            let existing = self.storage.get_data(table_name, key).await?;
+
+            for column in &autoembed_columns {
+                let source_text = column
+                    .source_columns
+                    .iter()
+                    .filter_map(|source| data.get(source).and_then(Value::as_str))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                if source_text.is_empty() {
+                    continue;
+                }
+
+                let sources_unchanged = existing
+                    .as_ref()
+                    .is_some_and(|existing| column.source_columns.iter().all(|source| existing.get(source) == data.get(source)));
+
+                if sources_unchanged {
+                    if let Some(previous_embedding) = existing.as_ref().and_then(|e| e.get(&column.vector_column)).cloned() {
+                        data[column.vector_column.clone()] = previous_embedding;
+                        continue;
+                    }
+                }
+
+                let embedding = self.ai_runtime.generate_embedding(&source_text).await?;
+                data[column.vector_column.clone()] = serde_json::json!(embedding);
+
+                self.vector_index
+                    .insert_vector(
+                        table_name,
+                        &column.vector_column,
+                        row_id_from_key(key),
+                        &embedding,
+                        serde_json::json!({ "key": key, "text": source_text }),
+                    )
+                    .await?;
+            }
+        }
+
+        self.storage.insert_data(table_name, key, &data).await
     }
     
+    pub async fn list_tables(&self) -> Result<Vec<crate::storage::TableMetadata>> {
+        self.storage.list_tables().await
+    }
+
+    pub async fn get_table_info(&self, table_name: &str) -> Result<Option<crate::storage::TableMetadata>> {
+        self.storage.get_table_info(table_name).await
+    }
+
+    /// Aggregates every table's metadata into one summary for `/stats` -
+    /// total tables, rows and bytes, alongside each table's own entry.
+    pub async fn get_stats(&self) -> Result<Value> {
+        let tables = self.storage.list_tables().await?;
+        let total_rows: u64 = tables.iter().map(|t| t.row_count).sum();
+        let total_size_bytes: u64 = tables.iter().map(|t| t.size_bytes).sum();
+
+        Ok(serde_json::json!({
+            "table_count": tables.len(),
+            "total_rows": total_rows,
+            "total_size_bytes": total_size_bytes,
+            "tables": tables,
+        }))
+    }
+
     pub async fn create_vector_index(&self, table_name: &str, column_name: &str) -> Result<()> {
         // Create HNSW index on vector column
         self.vector_index.create_index(table_name, column_name).await?;
         Ok(())
     }
+
+    pub async fn insert_vector(
+        &self,
+        table_name: &str,
+        column_name: &str,
+        id: u32,
+        vector: &[f32],
+        metadata: Value,
+    ) -> Result<()> {
+        self.vector_index
+            .insert_vector(table_name, column_name, id, vector, metadata)
+            .await
+    }
+
+    /// Splits `text` into overlapping passages (`AIRuntime::embed_document`)
+    /// and indexes each one separately, so `vector_search` can surface the
+    /// matching passage of a long document rather than one diluted
+    /// whole-row vector. Every chunk's metadata carries `row_id` so results
+    /// can be traced back to the source row. Returns the number of chunks
+    /// indexed.
+    pub async fn embed_and_index_document(
+        &self,
+        table_name: &str,
+        column_name: &str,
+        row_id: u32,
+        text: &str,
+    ) -> Result<usize> {
+        let chunks = self.ai_runtime.embed_document(text).await?;
+        let chunk_count = chunks.len();
+
+        for (chunk_index, (range, embedding)) in chunks.into_iter().enumerate() {
+            let chunk_id = self.next_chunk_id.fetch_add(1, Ordering::SeqCst);
+            let metadata = serde_json::json!({
+                "row_id": row_id,
+                "chunk_index": chunk_index,
+                "start": range.start,
+                "end": range.end,
+                "text": &text[range],
+            });
+
+            self.vector_index
+                .insert_vector(table_name, column_name, chunk_id, &embedding, metadata)
+                .await?;
+        }
+
+        Ok(chunk_count)
+    }
     
     fn record_batches_to_json(&self, batches: Vec<RecordBatch>) -> Result<Value> {
         // Convert Arrow RecordBatches to JSON
@@ -98,6 +426,38 @@ impl VectraEngine {
     }
 }
 
+/// Merges `semantic` and `keyword` result lists (each already sorted best
+/// first, as returned by `vector_search`/`keyword_search`) via Reciprocal
+/// Rank Fusion: a document at 1-based rank `r` in a list contributes
+/// `semantic_ratio (or 1 - semantic_ratio) * 1 / (RRF_K + r)` to its fused
+/// score, summed across both lists it may appear in.
+fn reciprocal_rank_fusion(semantic: &[Value], keyword: &[Value], semantic_ratio: f32, limit: usize) -> Vec<Value> {
+    let semantic_weight = semantic_ratio.clamp(0.0, 1.0) as f64;
+    let keyword_weight = 1.0 - semantic_weight;
+
+    let mut fused: HashMap<u64, (f64, Value)> = HashMap::new();
+
+    for (list, weight) in [(semantic, semantic_weight), (keyword, keyword_weight)] {
+        for (rank, result) in list.iter().enumerate() {
+            let Some(id) = result["id"].as_u64() else { continue };
+            let contribution = weight * (1.0 / (RRF_K + (rank + 1) as f64));
+            fused.entry(id).or_insert_with(|| (0.0, result.clone())).0 += contribution;
+        }
+    }
+
+    let mut results: Vec<(f64, Value)> = fused.into_values().collect();
+    results.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    results
+        .into_iter()
+        .take(limit)
+        .map(|(score, mut value)| {
+            value["score"] = serde_json::json!(score);
+            value
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone)]
 pub struct StreamSubscription {
     pub id: String,
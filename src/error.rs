@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+/// Storage-layer errors callers may want to match on, as opposed to the
+/// generic `anyhow::Error` used for "can't happen in practice" failures.
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("table '{table}' quota exceeded: {reason}")]
+    QuotaExceeded { table: String, reason: String },
+}
+
+/// Job-layer errors callers may want to match on, as opposed to the generic
+/// `anyhow::Error` used for "can't happen in practice" failures.
+#[derive(Debug, Error)]
+pub enum JobError {
+    #[error("unknown job: {0}")]
+    NotFound(String),
+}
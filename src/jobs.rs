@@ -0,0 +1,528 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+use crate::ai::AIRuntime;
+use crate::error::JobError;
+use crate::storage::StorageManager;
+use crate::vector::{row_id_from_key, VectorIndex};
+
+const JOBS_TABLE: &str = "embedding_jobs";
+
+/// Declares a recurring "re-embed changed rows" task - the CLI's
+/// `job create` maps directly onto this.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSpec {
+    pub name: String,
+    pub table: String,
+    pub primary_key: String,
+    pub source_columns: Vec<String>,
+    pub vector_column: String,
+    pub transformer: String,
+    pub schedule: String,
+}
+
+/// A job's spec plus its run history, persisted as a single row in
+/// `JOBS_TABLE` so jobs survive a process restart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobState {
+    pub spec: JobSpec,
+    pub last_run: Option<DateTime<Utc>>,
+    pub last_run_count: usize,
+    /// `seahash` of each row's concatenated source-column text as of the
+    /// last successful run, so `run_job` only re-embeds rows that actually
+    /// changed (or are new) instead of the whole table every tick.
+    row_fingerprints: HashMap<String, u64>,
+}
+
+impl JobState {
+    /// Rows of the table that no run has fingerprinted yet:
+    /// `current_row_count` minus the rows recorded by past runs. Rows that
+    /// changed since their last embedding are not counted. `job list`'s
+    /// "backlog" column is this count evaluated on demand.
+    pub fn backlog(&self, current_row_count: usize) -> usize {
+        current_row_count.saturating_sub(self.row_fingerprints.len())
+    }
+}
+
+/// Minimal 5-field cron (`minute hour day-of-month month day-of-week`)
+/// evaluator supporting `*`, a bare number, and `*/N` step syntax - enough
+/// to express "every N minutes/hours", which covers the re-embedding
+/// schedules this job system targets.
+pub struct CronSchedule {
+    minute: CronField,
+    hour: CronField,
+    day_of_month: CronField,
+    month: CronField,
+    day_of_week: CronField,
+}
+
+enum CronField {
+    Any,
+    Step(u32),
+    Value(u32),
+}
+
+impl CronField {
+    fn parse(field: &str) -> Result<Self> {
+        if field == "*" {
+            Ok(CronField::Any)
+        } else if let Some(step) = field.strip_prefix("*/") {
+            let step = step.parse().map_err(|_| anyhow!("invalid cron step '{}'", field))?;
+            Ok(CronField::Step(step))
+        } else {
+            let value = field.parse().map_err(|_| anyhow!("invalid cron field '{}'", field))?;
+            Ok(CronField::Value(value))
+        }
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        match self {
+            CronField::Any => true,
+            CronField::Step(step) => *step > 0 && value % step == 0,
+            CronField::Value(expected) => value == *expected,
+        }
+    }
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(anyhow!(
+                "cron expression '{}' must have exactly 5 fields (minute hour day-of-month month day-of-week)",
+                expr
+            ));
+        }
+
+        Ok(Self {
+            minute: CronField::parse(fields[0])?,
+            hour: CronField::parse(fields[1])?,
+            day_of_month: CronField::parse(fields[2])?,
+            month: CronField::parse(fields[3])?,
+            day_of_week: CronField::parse(fields[4])?,
+        })
+    }
+
+    fn matches(&self, time: DateTime<Utc>) -> bool {
+        self.minute.matches(time.minute())
+            && self.hour.matches(time.hour())
+            && self.day_of_month.matches(time.day())
+            && self.month.matches(time.month())
+            && self.day_of_week.matches(time.weekday().num_days_from_sunday())
+    }
+
+    /// A job is due once per matching minute: `matches` the current time,
+    /// and hasn't already run during this same minute (so a tick loop that
+    /// wakes slightly early/late doesn't double-fire).
+    fn is_due(&self, now: DateTime<Utc>, last_run: Option<DateTime<Utc>>) -> bool {
+        if !self.matches(now) {
+            return false;
+        }
+
+        match last_run {
+            Some(last_run) => {
+                last_run.date_naive() != now.date_naive()
+                    || last_run.hour() != now.hour()
+                    || last_run.minute() != now.minute()
+            }
+            None => true,
+        }
+    }
+}
+
+/// Persists job specs, evaluates their schedules, and re-embeds whichever
+/// rows changed since each job's last run. `JobScheduler::spawn` drives
+/// `tick` on an interval; `create_job`/`run_job_now`/etc. are the CLI's
+/// entry points via the `/jobs` routes.
+pub struct JobManager {
+    storage: Arc<StorageManager>,
+    ai_runtime: Arc<AIRuntime>,
+    vector_index: Arc<VectorIndex>,
+    jobs: Arc<RwLock<HashMap<String, JobState>>>,
+}
+
+impl JobManager {
+    pub async fn new(storage: Arc<StorageManager>, ai_runtime: Arc<AIRuntime>, vector_index: Arc<VectorIndex>) -> Result<Self> {
+        let manager = Self {
+            storage,
+            ai_runtime,
+            vector_index,
+            jobs: Arc::new(RwLock::new(HashMap::new())),
+        };
+        manager.load_persisted_jobs().await?;
+        Ok(manager)
+    }
+
+    async fn load_persisted_jobs(&self) -> Result<()> {
+        let mut jobs = self.jobs.write().await;
+        for (_, value) in self.storage.scan_table(JOBS_TABLE).await? {
+            if let Ok(state) = serde_json::from_value::<JobState>(value) {
+                jobs.insert(state.spec.name.clone(), state);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn create_job(&self, spec: JobSpec) -> Result<()> {
+        CronSchedule::parse(&spec.schedule)?;
+
+        let state = JobState {
+            spec: spec.clone(),
+            last_run: None,
+            last_run_count: 0,
+            row_fingerprints: HashMap::new(),
+        };
+        self.storage.insert_data(JOBS_TABLE, &spec.name, &serde_json::to_value(&state)?).await?;
+        self.jobs.write().await.insert(spec.name.clone(), state);
+        Ok(())
+    }
+
+    pub async fn delete_job(&self, name: &str) -> Result<()> {
+        self.jobs.write().await.remove(name);
+        self.storage.delete_data(JOBS_TABLE, name).await
+    }
+
+    pub async fn list_jobs(&self) -> Vec<JobState> {
+        self.jobs.read().await.values().cloned().collect()
+    }
+
+    pub async fn get_job(&self, name: &str) -> Option<JobState> {
+        self.jobs.read().await.get(name).cloned()
+    }
+
+    /// Forces an immediate run of `name`, bypassing its schedule - `job run`.
+    pub async fn run_job_now(&self, name: &str) -> Result<usize> {
+        let spec = self
+            .jobs
+            .read()
+            .await
+            .get(name)
+            .map(|state| state.spec.clone())
+            .ok_or_else(|| JobError::NotFound(name.to_string()))?;
+
+        self.run_job(&spec).await
+    }
+
+    /// Evaluates every job's cron schedule against `now` and runs the ones
+    /// that are due. Called on a fixed interval by `JobScheduler::spawn`.
+    async fn tick(&self, now: DateTime<Utc>) {
+        let due: Vec<JobSpec> = {
+            let jobs = self.jobs.read().await;
+            jobs.values()
+                .filter(|state| {
+                    CronSchedule::parse(&state.spec.schedule)
+                        .map(|schedule| schedule.is_due(now, state.last_run))
+                        .unwrap_or(false)
+                })
+                .map(|state| state.spec.clone())
+                .collect()
+        };
+
+        for spec in due {
+            if let Err(e) = self.run_job(&spec).await {
+                tracing::warn!("scheduled job '{}' failed: {}", spec.name, e);
+            }
+        }
+    }
+
+    /// Scans `spec.table`, re-embeds every row whose source columns are new
+    /// or changed since the last run (using `spec.transformer` explicitly,
+    /// via `AIRuntime::generate_embedding_with_model` - never the shared
+    /// "active" model, since multiple jobs with different transformers can
+    /// run concurrently), and persists the updated fingerprints. Returns the
+    /// number of rows re-embedded.
+    async fn run_job(&self, spec: &JobSpec) -> Result<usize> {
+        let mut fingerprints = self.get_job(&spec.name).await.map(|state| state.row_fingerprints).unwrap_or_default();
+
+        let reembedded = self.reembed_changed_rows(spec, &mut fingerprints).await?;
+
+        let state = JobState {
+            spec: spec.clone(),
+            last_run: Some(Utc::now()),
+            last_run_count: reembedded,
+            row_fingerprints: fingerprints,
+        };
+        self.storage.insert_data(JOBS_TABLE, &spec.name, &serde_json::to_value(&state)?).await?;
+        self.jobs.write().await.insert(spec.name.clone(), state);
+
+        Ok(reembedded)
+    }
+
+    async fn reembed_changed_rows(&self, spec: &JobSpec, fingerprints: &mut HashMap<String, u64>) -> Result<usize> {
+        let mut reembedded = 0;
+
+        for (key, mut data) in self.storage.scan_table(&spec.table).await? {
+            let source_text = spec
+                .source_columns
+                .iter()
+                .filter_map(|column| data.get(column).and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            if source_text.is_empty() {
+                continue;
+            }
+
+            let fingerprint = seahash::hash(source_text.as_bytes());
+            if fingerprints.get(&key) == Some(&fingerprint) {
+                continue;
+            }
+
+            let embedding = self.ai_runtime.generate_embedding_with_model(&spec.transformer, &source_text).await?;
+            data[spec.vector_column.clone()] = serde_json::json!(embedding);
+            self.storage.insert_data(&spec.table, &key, &data).await?;
+
+            self.vector_index
+                .insert_vector(
+                    &spec.table,
+                    &spec.vector_column,
+                    row_id_from_key(&key),
+                    &embedding,
+                    serde_json::json!({ "key": key.clone() }),
+                )
+                .await?;
+
+            fingerprints.insert(key, fingerprint);
+            reembedded += 1;
+        }
+
+        Ok(reembedded)
+    }
+}
+
+/// Drives `JobManager::tick` once per minute, matching cron's own
+/// granularity.
+pub struct JobScheduler;
+
+impl JobScheduler {
+    pub fn spawn(manager: Arc<JobManager>) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(60));
+            loop {
+                ticker.tick().await;
+                manager.tick(Utc::now()).await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::embedding::EmbeddingProvider;
+    use chrono::TimeZone;
+
+    struct MockEmbeddingProvider;
+
+    #[async_trait::async_trait]
+    impl EmbeddingProvider for MockEmbeddingProvider {
+        async fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+            // Matches `VectorIndex`'s hardcoded 384-dimension default so the
+            // re-embedded vector can actually be inserted into the index.
+            Ok(texts.iter().map(|_| vec![0.1; 384]).collect())
+        }
+
+        fn dimensions(&self) -> usize {
+            384
+        }
+
+        fn max_input_tokens(&self) -> usize {
+            8192
+        }
+    }
+
+    /// A second provider whose output is trivially distinguishable from
+    /// `MockEmbeddingProvider`'s, so a concurrent-jobs test can tell which
+    /// model actually produced a given row's embedding.
+    struct OtherMockEmbeddingProvider;
+
+    #[async_trait::async_trait]
+    impl EmbeddingProvider for OtherMockEmbeddingProvider {
+        async fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+            Ok(texts.iter().map(|_| vec![0.9; 384]).collect())
+        }
+
+        fn dimensions(&self) -> usize {
+            384
+        }
+
+        fn max_input_tokens(&self) -> usize {
+            8192
+        }
+    }
+
+    async fn test_manager() -> (JobManager, Arc<StorageManager>, Config) {
+        let config = Config::default();
+        let storage = Arc::new(StorageManager::new(&config).await.unwrap());
+        let ai_runtime = Arc::new(AIRuntime::new(&config, storage.clone()).await.unwrap());
+        ai_runtime
+            .register_embedding_provider(&config.ai.embedding_model, Box::new(MockEmbeddingProvider))
+            .await;
+
+        storage.create_table("docs", "id TEXT, title TEXT, embedding VECTOR(384)", None, None).await.unwrap();
+
+        let vector_index = Arc::new(VectorIndex::new(&config).await.unwrap());
+        vector_index.create_index("docs", "embedding").await.unwrap();
+
+        let manager = JobManager::new(storage.clone(), ai_runtime, vector_index).await.unwrap();
+        (manager, storage, config)
+    }
+
+    #[test]
+    fn test_cron_schedule_every_five_minutes() {
+        let schedule = CronSchedule::parse("*/5 * * * *").unwrap();
+        assert!(schedule.matches(Utc.with_ymd_and_hms(2026, 1, 1, 10, 10, 0).unwrap()));
+        assert!(!schedule.matches(Utc.with_ymd_and_hms(2026, 1, 1, 10, 11, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_cron_schedule_rejects_wrong_field_count() {
+        assert!(CronSchedule::parse("* * *").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_run_and_list_job() {
+        let (manager, storage, config) = test_manager().await;
+
+        manager
+            .create_job(JobSpec {
+                name: "reembed_docs".to_string(),
+                table: "docs".to_string(),
+                primary_key: "id".to_string(),
+                source_columns: vec!["title".to_string()],
+                vector_column: "embedding".to_string(),
+                transformer: config.ai.embedding_model.clone(),
+                schedule: "*/5 * * * *".to_string(),
+            })
+            .await
+            .unwrap();
+
+        storage
+            .insert_data("docs", "1", &serde_json::json!({"id": "1", "title": "hello world"}))
+            .await
+            .unwrap();
+
+        let reembedded = manager.run_job_now("reembed_docs").await.unwrap();
+        assert_eq!(reembedded, 1);
+
+        let jobs = manager.list_jobs().await;
+        assert_eq!(jobs.len(), 1);
+        assert!(jobs[0].last_run.is_some());
+        assert_eq!(jobs[0].last_run_count, 1);
+
+        let row = storage.get_data("docs", "1").await.unwrap().unwrap();
+        assert_eq!(row["embedding"], serde_json::json!(vec![0.1f32; 384]));
+
+        // Running again with no row changes re-embeds nothing.
+        let reembedded_again = manager.run_job_now("reembed_docs").await.unwrap();
+        assert_eq!(reembedded_again, 0);
+    }
+
+    #[tokio::test]
+    async fn test_delete_job_removes_it() {
+        let (manager, _storage, config) = test_manager().await;
+
+        manager
+            .create_job(JobSpec {
+                name: "job_a".to_string(),
+                table: "docs".to_string(),
+                primary_key: "id".to_string(),
+                source_columns: vec!["title".to_string()],
+                vector_column: "embedding".to_string(),
+                transformer: config.ai.embedding_model.clone(),
+                schedule: "0 * * * *".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(manager.list_jobs().await.len(), 1);
+
+        manager.delete_job("job_a").await.unwrap();
+        assert_eq!(manager.list_jobs().await.len(), 0);
+        assert!(manager.get_job("job_a").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_run_job_now_on_unknown_name_returns_typed_not_found() {
+        let (manager, _storage, _config) = test_manager().await;
+
+        let err = manager.run_job_now("does_not_exist").await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<JobError>(), Some(JobError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_jobs_with_different_transformers_do_not_cross_contaminate() {
+        let config = Config::default();
+        let storage = Arc::new(StorageManager::new(&config).await.unwrap());
+        let ai_runtime = Arc::new(AIRuntime::new(&config, storage.clone()).await.unwrap());
+        ai_runtime
+            .register_embedding_provider("model_a", Box::new(MockEmbeddingProvider))
+            .await;
+        ai_runtime
+            .register_embedding_provider("model_b", Box::new(OtherMockEmbeddingProvider))
+            .await;
+
+        storage.create_table("docs_a", "id TEXT, title TEXT, embedding VECTOR(384)", None, None).await.unwrap();
+        storage.create_table("docs_b", "id TEXT, title TEXT, embedding VECTOR(384)", None, None).await.unwrap();
+        storage
+            .insert_data("docs_a", "1", &serde_json::json!({"id": "1", "title": "hello"}))
+            .await
+            .unwrap();
+        storage
+            .insert_data("docs_b", "1", &serde_json::json!({"id": "1", "title": "world"}))
+            .await
+            .unwrap();
+
+        let vector_index = Arc::new(VectorIndex::new(&config).await.unwrap());
+        vector_index.create_index("docs_a", "embedding").await.unwrap();
+        vector_index.create_index("docs_b", "embedding").await.unwrap();
+
+        let manager = Arc::new(JobManager::new(storage.clone(), ai_runtime, vector_index).await.unwrap());
+
+        manager
+            .create_job(JobSpec {
+                name: "job_a".to_string(),
+                table: "docs_a".to_string(),
+                primary_key: "id".to_string(),
+                source_columns: vec!["title".to_string()],
+                vector_column: "embedding".to_string(),
+                transformer: "model_a".to_string(),
+                schedule: "*/5 * * * *".to_string(),
+            })
+            .await
+            .unwrap();
+        manager
+            .create_job(JobSpec {
+                name: "job_b".to_string(),
+                table: "docs_b".to_string(),
+                primary_key: "id".to_string(),
+                source_columns: vec!["title".to_string()],
+                vector_column: "embedding".to_string(),
+                transformer: "model_b".to_string(),
+                schedule: "*/5 * * * *".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let manager_a = manager.clone();
+        let manager_b = manager.clone();
+        let (result_a, result_b) = tokio::join!(
+            tokio::spawn(async move { manager_a.run_job_now("job_a").await.unwrap() }),
+            tokio::spawn(async move { manager_b.run_job_now("job_b").await.unwrap() }),
+        );
+        assert_eq!(result_a.unwrap(), 1);
+        assert_eq!(result_b.unwrap(), 1);
+
+        let row_a = storage.get_data("docs_a", "1").await.unwrap().unwrap();
+        let row_b = storage.get_data("docs_b", "1").await.unwrap().unwrap();
+        assert_eq!(row_a["embedding"], serde_json::json!(vec![0.1f32; 384]), "job_a must use model_a's embedding");
+        assert_eq!(row_b["embedding"], serde_json::json!(vec![0.9f32; 384]), "job_b must use model_b's embedding");
+    }
+}
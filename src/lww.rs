@@ -0,0 +1,89 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Hybrid logical clock: the max of wall-clock millis and the last
+/// timestamp handed out plus one, so timestamps stay monotonic across
+/// back-to-back writes within the same millisecond and under clock skew
+/// between replicas.
+pub struct HybridLogicalClock {
+    last: AtomicU64,
+}
+
+impl HybridLogicalClock {
+    pub fn new() -> Self {
+        Self {
+            last: AtomicU64::new(0),
+        }
+    }
+
+    /// Produce the next timestamp for a local write.
+    pub fn tick(&self) -> u64 {
+        let wall = chrono::Utc::now().timestamp_millis().max(0) as u64;
+        loop {
+            let prev = self.last.load(Ordering::SeqCst);
+            let next = wall.max(prev + 1);
+            if self
+                .last
+                .compare_exchange(prev, next, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                return next;
+            }
+        }
+    }
+
+    /// Fold in a timestamp observed from elsewhere (a remote write during
+    /// replication) so our own clock never hands out one that's behind it.
+    pub fn observe(&self, timestamp: u64) {
+        let mut prev = self.last.load(Ordering::SeqCst);
+        while timestamp > prev {
+            match self
+                .last
+                .compare_exchange(prev, timestamp, Ordering::SeqCst, Ordering::SeqCst)
+            {
+                Ok(_) => break,
+                Err(actual) => prev = actual,
+            }
+        }
+    }
+}
+
+impl Default for HybridLogicalClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Last-writer-wins register, mirroring Garage's `table/crdt/lww.rs`. An
+/// incoming write only replaces the existing one if its timestamp is
+/// greater; on an exact tie, the serialized value bytes are compared so
+/// that merging the same set of writes in any order converges to the same
+/// result regardless of delivery order.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LwwValue {
+    pub timestamp: u64,
+    pub value: Value,
+}
+
+impl LwwValue {
+    pub fn new(timestamp: u64, value: Value) -> Self {
+        Self { timestamp, value }
+    }
+
+    /// Whether `incoming` should replace `existing` under LWW semantics.
+    pub fn should_replace(existing: Option<&LwwValue>, incoming: &LwwValue) -> Result<bool> {
+        let Some(existing) = existing else {
+            return Ok(true);
+        };
+
+        Ok(match incoming.timestamp.cmp(&existing.timestamp) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => {
+                serde_json::to_vec(&incoming.value)? > serde_json::to_vec(&existing.value)?
+            }
+        })
+    }
+}
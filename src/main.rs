@@ -2,9 +2,16 @@ use axum::{
     routing::{get, post},
     Router,
     http::StatusCode,
-    response::Json,
-    extract::State,
+    response::{
+        sse::{Event, Sse},
+        Json,
+    },
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, State,
+    },
 };
+use std::convert::Infallible;
 use std::sync::Arc;
 use tokio::net::TcpListener;
 use tracing::{info, error};
@@ -15,18 +22,39 @@ mod vector;
 mod streaming;
 mod ai;
 mod storage;
+mod storage_backend;
+mod merkle;
+mod chunking;
+mod lww;
+mod error;
 mod config;
+mod config_watcher;
 mod metrics;
 mod cache;
 mod sql_parser;
+mod otlp;
+mod embedding;
+mod queue;
+mod splitter;
+mod bm25;
+mod jobs;
 
 use engine::VectraEngine;
 use config::Config;
+use error::JobError;
+use metrics::{CacheMetricsRegistry, MetricsCollector};
+use cache::{QueryCache, VectorCache};
+use otlp::OtlpExporter;
+use config_watcher::ConfigWatcher;
 
 #[derive(Clone)]
 struct AppState {
     engine: Arc<VectraEngine>,
     config: Config,
+    query_cache: Arc<QueryCache>,
+    vector_cache: Arc<VectorCache>,
+    cache_metrics: Arc<CacheMetricsRegistry>,
+    metrics_collector: Arc<MetricsCollector>,
 }
 
 #[tokio::main]
@@ -48,15 +76,40 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let state = AppState {
         engine,
         config: config.clone(),
+        query_cache: Arc::new(QueryCache::new()),
+        vector_cache: Arc::new(VectorCache::new()),
+        cache_metrics: Arc::new(CacheMetricsRegistry::new()),
+        metrics_collector: Arc::new(MetricsCollector::new()),
     };
-    
+
+    // Push metrics to an OTLP collector on an interval, alongside the
+    // pull-based /metrics and /admin/stats endpoints. No-op if disabled.
+    OtlpExporter::spawn(state.metrics_collector.clone(), config.otlp.clone());
+
+    // Hot-reload the config file without a restart. Subsystems will
+    // subscribe to this receiver as they're wired up to re-apply changes.
+    let _config_rx = ConfigWatcher::spawn(config.clone());
+
     // Build router
     let app = Router::new()
         .route("/", get(root))
         .route("/health", get(health))
+        .route("/metrics", get(metrics_endpoint))
+        .route("/admin/stats", get(admin_stats))
         .route("/query", post(execute_query))
         .route("/vector/search", post(vector_search))
+        .route("/hybrid/search", post(hybrid_search))
+        .route("/rag", post(rag_query))
         .route("/stream/subscribe", post(subscribe_stream))
+        .route("/stream", get(stream_ws))
+        .route("/tables", get(list_tables).post(create_table))
+        .route("/tables/{table}", get(table_info))
+        .route("/tables/{table}/rows", post(insert_row))
+        .route("/index", post(create_index))
+        .route("/stats", get(get_stats))
+        .route("/jobs", get(list_jobs).post(create_job))
+        .route("/jobs/{name}", axum::routing::delete(delete_job))
+        .route("/jobs/{name}/run", post(run_job))
         .with_state(state);
     
     // Start server
@@ -85,6 +138,26 @@ async fn health() -> (StatusCode, Json<serde_json::Value>) {
     )
 }
 
+/// Scrapes the query/vector caches into the shared registry and renders
+/// their stats in Prometheus text exposition format, alongside whatever
+/// other subsystems later register into `cache_metrics`.
+async fn metrics_endpoint(State(state): State<AppState>) -> String {
+    state.query_cache.export_metrics(&state.cache_metrics).await;
+    state.vector_cache.export_metrics(&state.cache_metrics).await;
+    state.engine.export_ai_metrics(&state.cache_metrics).await;
+
+    let mut body = state.cache_metrics.export_prometheus().await;
+    body.push_str(&state.metrics_collector.export_prometheus().await);
+    body
+}
+
+/// JSON counterpart to `/metrics` for dashboards that don't parse the
+/// Prometheus exposition format.
+async fn admin_stats(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let snapshot = state.metrics_collector.snapshot().await;
+    Json(serde_json::to_value(snapshot).unwrap_or_default())
+}
+
 async fn execute_query(
     State(state): State<AppState>,
     Json(payload): Json<serde_json::Value>,
@@ -107,8 +180,10 @@ async fn vector_search(
         .as_str()
         .ok_or(StatusCode::BAD_REQUEST)?;
     let limit = payload["limit"].as_u64().unwrap_or(10);
-    
-    match state.engine.vector_search(query, limit as usize).await {
+    let table = payload["table"].as_str().unwrap_or("documents");
+    let column = payload["column"].as_str().unwrap_or("embedding");
+
+    match state.engine.vector_search(table, column, query, limit as usize).await {
         Ok(results) => Ok(Json(serde_json::json!({
             "results": results,
             "query": query,
@@ -118,6 +193,312 @@ async fn vector_search(
     }
 }
 
+async fn hybrid_search(
+    State(state): State<AppState>,
+    Json(payload): Json<serde_json::Value>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let query = payload["query"]
+        .as_str()
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    let limit = payload["limit"].as_u64().unwrap_or(10);
+    let table = payload["table"].as_str().unwrap_or("documents");
+    let column = payload["column"].as_str().unwrap_or("embedding");
+    let semantic_ratio = payload["semantic_ratio"].as_f64().unwrap_or(0.5) as f32;
+
+    match state
+        .engine
+        .hybrid_search(table, column, query, limit as usize, semantic_ratio)
+        .await
+    {
+        Ok(results) => Ok(Json(serde_json::json!({
+            "results": results,
+            "query": query,
+            "limit": limit,
+            "semantic_ratio": semantic_ratio
+        }))),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Upgrades to a GraphQL-over-WebSocket-style subscription protocol:
+/// `connection_init` -> `connection_ack`, then `subscribe` -> a stream of
+/// `next` frames until the client sends `complete` or disconnects. See
+/// `handle_stream_socket` for the frame loop.
+async fn stream_ws(State(state): State<AppState>, ws: WebSocketUpgrade) -> axum::response::Response {
+    ws.on_upgrade(|socket| handle_stream_socket(socket, state))
+}
+
+async fn handle_stream_socket(mut socket: WebSocket, state: AppState) {
+    let Some(Ok(Message::Text(init))) = socket.recv().await else {
+        return;
+    };
+    match serde_json::from_str::<serde_json::Value>(&init) {
+        Ok(frame) if frame["type"] == "connection_init" => {}
+        _ => return,
+    }
+    if socket
+        .send(Message::Text(serde_json::json!({ "type": "connection_ack" }).to_string().into()))
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    let Some(Ok(Message::Text(sub_frame))) = socket.recv().await else {
+        return;
+    };
+    let Ok(sub_frame) = serde_json::from_str::<serde_json::Value>(&sub_frame) else {
+        return;
+    };
+    if sub_frame["type"] != "subscribe" {
+        return;
+    }
+    let id = sub_frame["id"].as_str().unwrap_or_default().to_string();
+    let Some(topic) = sub_frame["payload"]["topic"].as_str().map(str::to_string) else {
+        return;
+    };
+
+    let subscription = match state.engine.subscribe_stream(&topic).await {
+        Ok(subscription) => subscription,
+        Err(e) => {
+            let _ = socket
+                .send(Message::Text(
+                    serde_json::json!({ "type": "error", "id": id, "payload": { "message": e.to_string() } })
+                        .to_string()
+                        .into(),
+                ))
+                .await;
+            return;
+        }
+    };
+
+    let mut poll_interval = tokio::time::interval(std::time::Duration::from_millis(250));
+
+    loop {
+        tokio::select! {
+            _ = poll_interval.tick() => {
+                match state.engine.poll_stream(&subscription.id, 50).await {
+                    Ok(messages) => {
+                        for (offset, payload) in messages {
+                            let frame = serde_json::json!({ "type": "next", "id": id, "payload": payload });
+                            if socket.send(Message::Text(frame.to_string().into())).await.is_err() {
+                                let _ = state.engine.unsubscribe_stream(&subscription.id).await;
+                                return;
+                            }
+                            let _ = state.engine.commit_stream_offset(&subscription.id, offset).await;
+                        }
+                    }
+                    Err(e) => {
+                        let frame = serde_json::json!({ "type": "error", "id": id, "payload": { "message": e.to_string() } });
+                        if socket.send(Message::Text(frame.to_string().into())).await.is_err() {
+                            let _ = state.engine.unsubscribe_stream(&subscription.id).await;
+                            return;
+                        }
+                    }
+                }
+            }
+            incoming = socket.recv() => {
+                match incoming {
+                    Some(Ok(Message::Text(text))) => {
+                        if let Ok(frame) = serde_json::from_str::<serde_json::Value>(&text) {
+                            if frame["type"] == "complete" {
+                                let _ = state.engine.unsubscribe_stream(&subscription.id).await;
+                                return;
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => {
+                        let _ = state.engine.unsubscribe_stream(&subscription.id).await;
+                        return;
+                    }
+                    Some(Err(_)) => {
+                        let _ = state.engine.unsubscribe_stream(&subscription.id).await;
+                        return;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+async fn create_table(
+    State(state): State<AppState>,
+    Json(payload): Json<serde_json::Value>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let table = payload["table"].as_str().ok_or(StatusCode::BAD_REQUEST)?;
+    let schema = payload["schema"].as_str().ok_or(StatusCode::BAD_REQUEST)?;
+
+    match state.engine.create_table(table, schema).await {
+        Ok(()) => Ok(Json(serde_json::json!({ "table": table, "status": "created" }))),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn insert_row(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+    Json(payload): Json<serde_json::Value>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let key = payload["key"].as_str().ok_or(StatusCode::BAD_REQUEST)?;
+    let data = payload.get("data").ok_or(StatusCode::BAD_REQUEST)?;
+
+    match state.engine.insert_data(&table, key, data).await {
+        Ok(()) => Ok(Json(serde_json::json!({ "table": table, "key": key, "status": "inserted" }))),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn create_index(
+    State(state): State<AppState>,
+    Json(payload): Json<serde_json::Value>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let table = payload["table"].as_str().ok_or(StatusCode::BAD_REQUEST)?;
+    let column = payload["column"].as_str().ok_or(StatusCode::BAD_REQUEST)?;
+
+    match state.engine.create_vector_index(table, column).await {
+        Ok(()) => Ok(Json(serde_json::json!({ "table": table, "column": column, "status": "created" }))),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn list_tables(State(state): State<AppState>) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.engine.list_tables().await {
+        Ok(tables) => Ok(Json(serde_json::json!({ "tables": tables }))),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn table_info(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.engine.get_table_info(&table).await {
+        Ok(Some(info)) => Ok(Json(serde_json::to_value(info).unwrap_or_default())),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn get_stats(State(state): State<AppState>) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.engine.get_stats().await {
+        Ok(stats) => Ok(Json(stats)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn create_job(
+    State(state): State<AppState>,
+    Json(payload): Json<serde_json::Value>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let spec: crate::jobs::JobSpec = serde_json::from_value(payload).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let name = spec.name.clone();
+
+    match state.engine.create_job(spec).await {
+        Ok(()) => Ok(Json(serde_json::json!({ "name": name, "status": "created" }))),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Lists every job alongside its schedule, last-run timestamp, and how many
+/// rows of its table haven't been picked up by a run yet.
+async fn list_jobs(State(state): State<AppState>) -> Result<Json<serde_json::Value>, StatusCode> {
+    let jobs = state.engine.list_jobs().await;
+    let mut entries = Vec::with_capacity(jobs.len());
+
+    for job in jobs {
+        let row_count = state
+            .engine
+            .get_table_info(&job.spec.table)
+            .await
+            .ok()
+            .flatten()
+            .map(|info| info.row_count as usize)
+            .unwrap_or(0);
+
+        entries.push(serde_json::json!({
+            "name": job.spec.name,
+            "table": job.spec.table,
+            "schedule": job.spec.schedule,
+            "transformer": job.spec.transformer,
+            "last_run": job.last_run,
+            "last_run_count": job.last_run_count,
+            "backlog": job.backlog(row_count),
+        }));
+    }
+
+    Ok(Json(serde_json::json!({ "jobs": entries })))
+}
+
+async fn run_job(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.engine.run_job_now(&name).await {
+        Ok(reembedded) => Ok(Json(serde_json::json!({ "name": name, "reembedded": reembedded }))),
+        Err(err) if err.downcast_ref::<JobError>().is_some_and(|e| matches!(e, JobError::NotFound(_))) => {
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn delete_job(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    match state.engine.delete_job(&name).await {
+        Ok(()) => Ok(Json(serde_json::json!({ "name": name, "status": "deleted" }))),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Retrieves context for `payload["query"]`, generates an answer against it,
+/// and streams the result as Server-Sent Events: one `sources` event with
+/// the retrieved snippets, a `token` event per word of the answer (the text
+/// model itself returns its answer in one shot - see `AIRuntime::generate_text`
+/// - so this is where the incremental delivery the CLI expects comes from),
+/// and a final `citations` event.
+async fn rag_query(
+    State(state): State<AppState>,
+    Json(payload): Json<serde_json::Value>,
+) -> Result<Sse<impl futures_util::Stream<Item = Result<Event, Infallible>>>, StatusCode> {
+    let query = payload["query"].as_str().ok_or(StatusCode::BAD_REQUEST)?;
+    let table = payload["table"].as_str().unwrap_or("documents");
+    let column = payload["column"].as_str().unwrap_or("embedding");
+    let limit = payload["limit"].as_u64().unwrap_or(5) as usize;
+    let model = payload["model"].as_str();
+
+    let (sources, answer) = state
+        .engine
+        .rag_query(table, column, query, limit, model)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let mut events = Vec::new();
+
+    events.push(
+        Event::default()
+            .event("sources")
+            .json_data(&sources)
+            .unwrap_or_else(|_| Event::default().event("sources").data("[]")),
+    );
+
+    for word in answer.split_inclusive(' ') {
+        events.push(Event::default().event("token").data(word.to_string()));
+    }
+
+    let citations: Vec<serde_json::Value> = sources.iter().map(|source| source["id"].clone()).collect();
+    events.push(
+        Event::default()
+            .event("citations")
+            .json_data(&citations)
+            .unwrap_or_else(|_| Event::default().event("citations").data("[]")),
+    );
+
+    Ok(Sse::new(futures_util::stream::iter(events.into_iter().map(Ok))))
+}
+
 async fn subscribe_stream(
     State(state): State<AppState>,
     Json(payload): Json<serde_json::Value>,
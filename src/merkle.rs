@@ -0,0 +1,213 @@
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+
+use crate::storage_backend::StorageBackend;
+
+/// Depth of the tree in nibbles. 4 nibbles gives 65536 leaf buckets, which
+/// keeps individual rows cheap to relocate while leaving root-to-leaf walks
+/// shallow; buckets are only ever materialized lazily as rows land in them.
+const MERKLE_DEPTH: usize = 4;
+const ZERO_HASH: [u8; 32] = [0u8; 32];
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn nibble_char(nibble: u8) -> char {
+    std::char::from_digit(nibble as u32, 16).unwrap()
+}
+
+/// Per-table Merkle tree used to detect divergence between replicas (or
+/// between storage backends during a migration) without scanning whole
+/// tables. Nodes are addressed by a hex nibble path and persisted under the
+/// reserved `merkle` tree of the owning backend: `merkle:{table}:{path}`.
+pub struct MerkleIndex<'a> {
+    backend: &'a dyn StorageBackend,
+}
+
+impl<'a> MerkleIndex<'a> {
+    pub fn new(backend: &'a dyn StorageBackend) -> Self {
+        Self { backend }
+    }
+
+    fn leaf_path(key: &str) -> String {
+        let digest = Sha256::digest(key.as_bytes());
+        (0..MERKLE_DEPTH)
+            .map(|nibble_index| {
+                let byte = digest[nibble_index / 2];
+                let nibble = if nibble_index % 2 == 0 { byte >> 4 } else { byte & 0x0f };
+                nibble_char(nibble)
+            })
+            .collect()
+    }
+
+    fn node_key(table: &str, path: &str) -> String {
+        format!("merkle:{}:{}", table, path)
+    }
+
+    fn keys_key(table: &str, path: &str) -> String {
+        format!("merkle_keys:{}:{}", table, path)
+    }
+
+    fn read_hash(&self, table: &str, path: &str) -> Result<[u8; 32]> {
+        match self.backend.get("merkle", Self::node_key(table, path).as_bytes())? {
+            Some(bytes) if bytes.len() == 32 => {
+                let mut hash = [0u8; 32];
+                hash.copy_from_slice(&bytes);
+                Ok(hash)
+            }
+            _ => Ok(ZERO_HASH),
+        }
+    }
+
+    fn write_hash(&self, table: &str, path: &str, hash: [u8; 32]) -> Result<()> {
+        self.backend.put("merkle", Self::node_key(table, path).as_bytes(), &hash)
+    }
+
+    /// Bucket entries as `(key, hex sha256(value_bytes))` pairs. The value
+    /// hash rides alongside the key so the leaf hash below can fold in value
+    /// changes, not just key membership.
+    fn read_bucket(&self, table: &str, path: &str) -> Result<Vec<(String, String)>> {
+        match self.backend.get("merkle", Self::keys_key(table, path).as_bytes())? {
+            Some(bytes) => Ok(serde_json::from_slice(&bytes).unwrap_or_default()),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Recompute the leaf bucket `key` falls into and propagate the new hash
+    /// up to the root. Called from `insert_data`/`delete_data` so dirtied
+    /// nodes are always in sync with the last write.
+    pub fn update_leaf(&self, table: &str, key: &str, value_bytes: Option<&[u8]>) -> Result<()> {
+        let leaf_path = Self::leaf_path(key);
+        let mut entries = self.read_bucket(table, &leaf_path)?;
+        entries.retain(|(k, _)| k != key);
+
+        if let Some(bytes) = value_bytes {
+            let value_hash = to_hex(&Sha256::digest(bytes));
+            entries.push((key.to_string(), value_hash));
+        }
+
+        // Canonicalize by key rather than insertion order, so two replicas
+        // holding the same rows in a different insertion order still hash
+        // the bucket identically - otherwise anti-entropy would report a
+        // spurious divergence (or never converge) on identical data.
+        entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        self.backend
+            .put("merkle", Self::keys_key(table, &leaf_path).as_bytes(), &serde_json::to_vec(&entries)?)?;
+
+        // The leaf hash covers every key *and* value in the bucket, not just
+        // the one that changed, so both membership changes (insert/delete)
+        // and in-place value overwrites are reflected correctly.
+        let mut leaf_hasher = Sha256::new();
+        for (bucket_key, value_hash) in &entries {
+            leaf_hasher.update(Sha256::digest(bucket_key.as_bytes()));
+            leaf_hasher.update(value_hash.as_bytes());
+        }
+        self.write_hash(table, &leaf_path, leaf_hasher.finalize().into())?;
+
+        for depth in (0..MERKLE_DEPTH).rev() {
+            let parent_prefix = &leaf_path[..depth];
+            let mut hasher = Sha256::new();
+            for nibble in 0..16u8 {
+                let child_path = format!("{}{}", parent_prefix, nibble_char(nibble));
+                hasher.update(self.read_hash(table, &child_path)?);
+            }
+            self.write_hash(table, parent_prefix, hasher.finalize().into())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn root_hash(&self, table: &str) -> Result<String> {
+        Ok(to_hex(&self.read_hash(table, "")?))
+    }
+
+    /// Walk both trees top-down, descending only into subtrees whose hashes
+    /// differ, and return the row keys living in divergent leaf buckets.
+    /// `other_node_hash` resolves a node path (on the peer being compared
+    /// against) to its hash, e.g. an RPC to another replica.
+    pub fn diff<F>(&self, table: &str, other_node_hash: F) -> Result<Vec<String>>
+    where
+        F: Fn(&str) -> Result<[u8; 32]>,
+    {
+        let mut divergent = Vec::new();
+        self.diff_subtree(table, "", &other_node_hash, &mut divergent)?;
+        Ok(divergent)
+    }
+
+    fn diff_subtree<F>(
+        &self,
+        table: &str,
+        path: &str,
+        other_node_hash: &F,
+        divergent: &mut Vec<String>,
+    ) -> Result<()>
+    where
+        F: Fn(&str) -> Result<[u8; 32]>,
+    {
+        if self.read_hash(table, path)? == other_node_hash(path)? {
+            return Ok(());
+        }
+
+        if path.len() == MERKLE_DEPTH {
+            divergent.extend(self.read_bucket(table, path)?.into_iter().map(|(k, _)| k));
+            return Ok(());
+        }
+
+        for nibble in 0..16u8 {
+            let child_path = format!("{}{}", path, nibble_char(nibble));
+            self.diff_subtree(table, &child_path, other_node_hash, divergent)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage_backend::SledBackend;
+
+    fn test_backend() -> (tempfile::TempDir, SledBackend) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let backend = SledBackend::open(&temp_dir.path().join("sled").to_string_lossy()).unwrap();
+        (temp_dir, backend)
+    }
+
+    #[test]
+    fn test_root_hash_is_independent_of_leaf_insertion_order() {
+        // "key68" and "key130" share a leaf bucket (their sha256 digests
+        // agree on the first `MERKLE_DEPTH` nibbles), so inserting them in
+        // opposite order across two replicas is enough to reproduce the bug:
+        // an un-canonicalized leaf hash would differ by insertion order even
+        // though both replicas end up with the same two rows.
+        let (_dir_a, backend_a) = test_backend();
+        let index_a = MerkleIndex::new(&backend_a);
+        index_a.update_leaf("users", "key68", Some(b"value")).unwrap();
+        index_a.update_leaf("users", "key130", Some(b"value")).unwrap();
+
+        let (_dir_b, backend_b) = test_backend();
+        let index_b = MerkleIndex::new(&backend_b);
+        index_b.update_leaf("users", "key130", Some(b"value")).unwrap();
+        index_b.update_leaf("users", "key68", Some(b"value")).unwrap();
+
+        assert_eq!(index_a.root_hash("users").unwrap(), index_b.root_hash("users").unwrap());
+    }
+
+    #[test]
+    fn test_root_hash_detects_value_divergence_on_same_key() {
+        // Two replicas holding the same key with different values must
+        // disagree on their root hash - otherwise anti-entropy can only ever
+        // catch key add/remove, never an update to an existing row.
+        let (_dir_a, backend_a) = test_backend();
+        let index_a = MerkleIndex::new(&backend_a);
+        index_a.update_leaf("users", "key68", Some(b"value-a")).unwrap();
+
+        let (_dir_b, backend_b) = test_backend();
+        let index_b = MerkleIndex::new(&backend_b);
+        index_b.update_leaf("users", "key68", Some(b"value-b")).unwrap();
+
+        assert_ne!(index_a.root_hash("users").unwrap(), index_b.root_hash("users").unwrap());
+    }
+}
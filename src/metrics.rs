@@ -31,27 +31,241 @@ pub struct HistogramBucket {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistogramMetric {
     pub name: String,
+    pub labels: HashMap<String, String>,
     pub buckets: Vec<HistogramBucket>,
     pub sum: f64,
     pub count: u64,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+/// Prometheus' own default histogram buckets, suitable for second-scale
+/// latencies. Used for any histogram that hasn't had
+/// `register_histogram_buckets` called for its name.
+const DEFAULT_HISTOGRAM_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Relative accuracy used by `DDSketch::new` when a summary doesn't pick
+/// its own - an estimate is guaranteed within this fraction of the true
+/// value at the requested quantile.
+const DEFAULT_SKETCH_ALPHA: f64 = 0.01;
+
+/// Values at or below this are bucketed separately rather than through
+/// `ln(v)`, which is undefined at zero and blows up for tiny values.
+const SKETCH_ZERO_THRESHOLD: f64 = 1e-9;
+
+/// Default idle TTL for counters/gauges before `sweep_expired` drops them.
+const DEFAULT_METRIC_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Default idle TTL for histograms/summaries - longer-lived than plain
+/// metrics since a quiet series is still cheap to keep a little longer, and
+/// bucket/sketch state is more expensive to have to rebuild from scratch.
+const DEFAULT_HISTOGRAM_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantileValue {
+    pub quantile: f64,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryMetric {
+    pub name: String,
+    pub labels: HashMap<String, String>,
+    pub sum: f64,
+    pub count: u64,
+    pub quantile_values: Vec<QuantileValue>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// A DDSketch: a relative-error quantile sketch whose memory is bounded by
+/// the number of distinct log-scale buckets touched, not the number of
+/// observations. Each positive value `v` maps to bucket
+/// `i = ceil(ln(v) / ln(gamma))` where `gamma = (1+alpha)/(1-alpha)`;
+/// collapsing a bucket back to the estimate `2*gamma^i/(gamma+1)`
+/// guarantees at most `alpha` relative error versus the true value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DDSketch {
+    alpha: f64,
+    gamma: f64,
+    bucket_counts: HashMap<i64, u64>,
+    zero_count: u64,
+    count: u64,
+    sum: f64,
+    min: f64,
+}
+
+impl DDSketch {
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha,
+            gamma: (1.0 + alpha) / (1.0 - alpha),
+            bucket_counts: HashMap::new(),
+            zero_count: 0,
+            count: 0,
+            sum: 0.0,
+            min: f64::INFINITY,
+        }
+    }
+
+    pub fn observe(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        if value < self.min {
+            self.min = value;
+        }
+
+        if value <= SKETCH_ZERO_THRESHOLD {
+            self.zero_count += 1;
+            return;
+        }
+
+        let index = (value.ln() / self.gamma.ln()).ceil() as i64;
+        *self.bucket_counts.entry(index).or_insert(0) += 1;
+    }
+
+    /// Estimate the value at quantile `q` (in `0.0..=1.0`), accurate to
+    /// within this sketch's relative error `alpha`.
+    pub fn quantile(&self, q: f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+
+        let rank = ((q * self.count as f64).ceil() as u64).max(1);
+        if rank <= self.zero_count {
+            return 0.0;
+        }
+
+        let mut indices: Vec<i64> = self.bucket_counts.keys().copied().collect();
+        indices.sort_unstable();
+
+        let mut running = self.zero_count;
+        for index in &indices {
+            running += self.bucket_counts[index];
+            if running >= rank {
+                return 2.0 * self.gamma.powf(*index as f64) / (self.gamma + 1.0);
+            }
+        }
+
+        // Every bucket accounted for (can happen when `q` rounds up to
+        // exactly `count`) - the highest bucket is the best estimate.
+        indices
+            .last()
+            .map(|&index| 2.0 * self.gamma.powf(index as f64) / (self.gamma + 1.0))
+            .unwrap_or(self.min)
+    }
+}
+
+struct SummarySketchEntry {
+    name: String,
+    labels: HashMap<String, String>,
+    sketch: DDSketch,
+    quantiles: Vec<f64>,
+    timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// One label-set's value for a counter or gauge in a `Snapshot`, with its
+/// labels sorted so two snapshots of the same series compare equal
+/// regardless of insertion order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CounterEntry {
+    pub labels: Vec<(String, String)>,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistogramEntry {
+    pub labels: Vec<(String, String)>,
+    pub sum: f64,
+    pub count: u64,
+    pub buckets: Vec<HistogramBucket>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryEntry {
+    pub labels: Vec<(String, String)>,
+    pub sum: f64,
+    pub count: u64,
+    pub quantiles: Vec<QuantileValue>,
+}
+
+/// A point-in-time, serde-serializable view of every series the collector
+/// holds, grouped by name - the JSON counterpart to `export_prometheus`'s
+/// text format, for dashboards that don't parse the exposition format and
+/// for tests that want structured assertions instead of string-scraping.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub counters: HashMap<String, Vec<CounterEntry>>,
+    pub gauges: HashMap<String, Vec<CounterEntry>>,
+    pub histograms: HashMap<String, Vec<HistogramEntry>>,
+    pub summaries: HashMap<String, Vec<SummaryEntry>>,
+    pub descriptions: HashMap<String, String>,
+    pub uptime_seconds: f64,
+}
+
 pub struct MetricsCollector {
     metrics: Arc<RwLock<HashMap<String, Metric>>>,
     histograms: Arc<RwLock<HashMap<String, HistogramMetric>>>,
+    /// Bucket boundaries registered per histogram name via
+    /// `register_histogram_buckets`, consulted the first time that
+    /// histogram is observed. Unregistered names fall back to
+    /// `DEFAULT_HISTOGRAM_BUCKETS`.
+    histogram_buckets: Arc<RwLock<HashMap<String, Vec<f64>>>>,
+    summaries: Arc<RwLock<HashMap<String, SummarySketchEntry>>>,
+    /// HELP text registered per metric name via `describe`, surfaced in
+    /// both the Prometheus `# HELP` lines and `Snapshot::descriptions`.
+    descriptions: Arc<RwLock<HashMap<String, String>>>,
+    /// How long a counter/gauge can go unobserved before `sweep_expired`
+    /// drops it.
+    metric_ttl: Duration,
+    /// How long a histogram/summary (pricier to rebuild - buckets, not
+    /// just one value) can go unobserved before it's dropped.
+    histogram_ttl: Duration,
     start_time: Instant,
 }
 
 impl MetricsCollector {
     pub fn new() -> Self {
+        Self::with_ttls(DEFAULT_METRIC_TTL, DEFAULT_HISTOGRAM_TTL)
+    }
+
+    /// Like `new`, but with explicit idle TTLs instead of the defaults (5
+    /// minutes for counters/gauges, 1 day for histograms/summaries).
+    pub fn with_ttls(metric_ttl: Duration, histogram_ttl: Duration) -> Self {
         Self {
             metrics: Arc::new(RwLock::new(HashMap::new())),
             histograms: Arc::new(RwLock::new(HashMap::new())),
+            histogram_buckets: Arc::new(RwLock::new(HashMap::from([(
+                // Vector search latencies are sub-millisecond; the
+                // second-scale default buckets would put every observation
+                // in the first bucket.
+                "vector_search_duration_seconds".to_string(),
+                vec![0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05],
+            )]))),
+            summaries: Arc::new(RwLock::new(HashMap::new())),
+            descriptions: Arc::new(RwLock::new(HashMap::new())),
+            metric_ttl,
+            histogram_ttl,
             start_time: Instant::now(),
         }
     }
-    
+
+    /// Register the bucket boundaries a histogram should use once it's
+    /// first observed, e.g. sub-millisecond buckets for
+    /// `vector_search_duration_seconds` versus the second-scale default
+    /// that suits `storage_operation_duration_seconds`. Has no effect on a
+    /// histogram that's already been observed - call this before the first
+    /// `observe_histogram` for `name`.
+    pub async fn register_histogram_buckets(&self, name: &str, buckets: Vec<f64>) {
+        self.histogram_buckets.write().await.insert(name.to_string(), buckets);
+    }
+
+    /// Attach HELP/description text to a metric name, surfaced as a
+    /// `# HELP` line in `export_prometheus` and in `Snapshot::descriptions`.
+    pub async fn describe(&self, name: &str, help: &str) {
+        self.descriptions.write().await.insert(name.to_string(), help.to_string());
+    }
+
     pub async fn increment_counter(&self, name: &str, labels: Option<HashMap<String, String>>) {
         let key = self.metric_key(name, labels.as_ref());
         let mut metrics = self.metrics.write().await;
@@ -101,19 +315,24 @@ impl MetricsCollector {
                 }
             }
         } else {
-            // Create new histogram with default buckets
-            let buckets = vec![
-                HistogramBucket { le: 0.1, count: 0 },
-                HistogramBucket { le: 0.5, count: 0 },
-                HistogramBucket { le: 1.0, count: 0 },
-                HistogramBucket { le: 2.5, count: 0 },
-                HistogramBucket { le: 5.0, count: 0 },
-                HistogramBucket { le: 10.0, count: 0 },
-                HistogramBucket { le: f64::INFINITY, count: 0 },
-            ];
-            
+            // Create a new histogram using this name's registered bucket
+            // boundaries, if any, falling back to the Prometheus defaults.
+            let registered = self.histogram_buckets.read().await;
+            let boundaries = registered
+                .get(name)
+                .map(|b| b.as_slice())
+                .unwrap_or(DEFAULT_HISTOGRAM_BUCKETS);
+
+            let mut buckets: Vec<HistogramBucket> = boundaries
+                .iter()
+                .map(|&le| HistogramBucket { le, count: 0 })
+                .collect();
+            buckets.push(HistogramBucket { le: f64::INFINITY, count: 0 });
+            drop(registered);
+
             let mut histogram = HistogramMetric {
                 name: name.to_string(),
+                labels: labels.unwrap_or_default(),
                 buckets,
                 sum: value,
                 count: 1,
@@ -131,6 +350,33 @@ impl MetricsCollector {
         }
     }
     
+    /// Observe `value` for the summary `name`, tracking it in a DDSketch
+    /// (memory bounded by distinct log-scale buckets touched, not the
+    /// number of observations) so `quantiles` (e.g. `&[0.5, 0.9, 0.99]`)
+    /// can be estimated on export within the sketch's relative error.
+    pub async fn observe_summary(
+        &self,
+        name: &str,
+        value: f64,
+        quantiles: &[f64],
+        labels: Option<HashMap<String, String>>,
+    ) {
+        let key = self.metric_key(name, labels.as_ref());
+        let mut summaries = self.summaries.write().await;
+
+        let entry = summaries.entry(key).or_insert_with(|| SummarySketchEntry {
+            name: name.to_string(),
+            labels: labels.unwrap_or_default(),
+            sketch: DDSketch::new(DEFAULT_SKETCH_ALPHA),
+            quantiles: quantiles.to_vec(),
+            timestamp: chrono::Utc::now(),
+        });
+
+        entry.sketch.observe(value);
+        entry.quantiles = quantiles.to_vec();
+        entry.timestamp = chrono::Utc::now();
+    }
+
     pub async fn record_query_duration(&self, query_type: &str, duration: Duration) {
         let labels = {
             let mut map = HashMap::new();
@@ -173,31 +419,139 @@ impl MetricsCollector {
         self.observe_histogram("ai_operation_duration_seconds", duration.as_secs_f64(), Some(labels)).await;
     }
     
+    /// Drop any counter/gauge/histogram/summary whose last observation is
+    /// older than its TTL, even if nothing has written to it - a series
+    /// that's only being scraped, not updated, still ages out. Safe to call
+    /// from a background `tokio::time::interval` loop as well as from the
+    /// scrape path itself.
+    pub async fn sweep_expired(&self) {
+        let now = chrono::Utc::now();
+        let metric_ttl = chrono::Duration::from_std(self.metric_ttl).unwrap_or(chrono::Duration::zero());
+        let histogram_ttl = chrono::Duration::from_std(self.histogram_ttl).unwrap_or(chrono::Duration::zero());
+
+        self.metrics.write().await.retain(|_, m| now - m.timestamp < metric_ttl);
+        self.histograms.write().await.retain(|_, h| now - h.timestamp < histogram_ttl);
+        self.summaries.write().await.retain(|_, s| now - s.timestamp < histogram_ttl);
+    }
+
     pub async fn get_metrics(&self) -> Vec<Metric> {
+        self.sweep_expired().await;
         let metrics = self.metrics.read().await;
         metrics.values().cloned().collect()
     }
-    
+
     pub async fn get_histograms(&self) -> Vec<HistogramMetric> {
+        self.sweep_expired().await;
         let histograms = self.histograms.read().await;
         histograms.values().cloned().collect()
     }
-    
+
+    pub async fn get_summaries(&self) -> Vec<SummaryMetric> {
+        self.sweep_expired().await;
+        let summaries = self.summaries.read().await;
+        summaries
+            .values()
+            .map(|entry| SummaryMetric {
+                name: entry.name.clone(),
+                labels: entry.labels.clone(),
+                sum: entry.sketch.sum,
+                count: entry.sketch.count,
+                quantile_values: entry
+                    .quantiles
+                    .iter()
+                    .map(|&q| QuantileValue {
+                        quantile: q,
+                        value: entry.sketch.quantile(q),
+                    })
+                    .collect(),
+                timestamp: entry.timestamp,
+            })
+            .collect()
+    }
+
+    /// A structured, serde-friendly view of every series currently held,
+    /// grouped by name - see `Snapshot`.
+    pub async fn snapshot(&self) -> Snapshot {
+        let mut counters: HashMap<String, Vec<CounterEntry>> = HashMap::new();
+        let mut gauges: HashMap<String, Vec<CounterEntry>> = HashMap::new();
+
+        for metric in self.get_metrics().await {
+            let mut labels: Vec<(String, String)> = metric.labels.into_iter().collect();
+            labels.sort();
+            let entry = CounterEntry { labels, value: metric.value };
+
+            match metric.metric_type {
+                MetricType::Counter => counters.entry(metric.name).or_default().push(entry),
+                MetricType::Gauge => gauges.entry(metric.name).or_default().push(entry),
+                MetricType::Histogram | MetricType::Summary => {}
+            }
+        }
+
+        let mut histograms: HashMap<String, Vec<HistogramEntry>> = HashMap::new();
+        for histogram in self.get_histograms().await {
+            let mut labels: Vec<(String, String)> = histogram.labels.into_iter().collect();
+            labels.sort();
+            histograms.entry(histogram.name).or_default().push(HistogramEntry {
+                labels,
+                sum: histogram.sum,
+                count: histogram.count,
+                buckets: histogram.buckets,
+            });
+        }
+
+        let mut summaries: HashMap<String, Vec<SummaryEntry>> = HashMap::new();
+        for summary in self.get_summaries().await {
+            let mut labels: Vec<(String, String)> = summary.labels.into_iter().collect();
+            labels.sort();
+            summaries.entry(summary.name).or_default().push(SummaryEntry {
+                labels,
+                sum: summary.sum,
+                count: summary.count,
+                quantiles: summary.quantile_values,
+            });
+        }
+
+        Snapshot {
+            counters,
+            gauges,
+            histograms,
+            summaries,
+            descriptions: self.descriptions.read().await.clone(),
+            uptime_seconds: self.get_uptime_seconds().await,
+        }
+    }
+
     pub async fn get_uptime_seconds(&self) -> f64 {
         self.start_time.elapsed().as_secs_f64()
     }
     
     pub async fn export_prometheus(&self) -> String {
         let mut output = String::new();
-        
+        let descriptions = self.descriptions.read().await.clone();
+
         // Add uptime
         let uptime = self.get_uptime_seconds().await;
         output.push_str(&format!("# HELP vectra_uptime_seconds Total uptime in seconds\n"));
         output.push_str(&format!("# TYPE vectra_uptime_seconds gauge\n"));
         output.push_str(&format!("vectra_uptime_seconds {}\n", uptime));
-        
+
         // Export metrics
         let metrics = self.get_metrics().await;
+        let mut described: std::collections::HashSet<&str> = std::collections::HashSet::new();
+        for metric in &metrics {
+            if described.insert(&metric.name) {
+                if let Some(help) = descriptions.get(&metric.name) {
+                    output.push_str(&format!("# HELP {} {}\n", metric.name, help));
+                }
+                let type_str = match metric.metric_type {
+                    MetricType::Counter => "counter",
+                    MetricType::Gauge => "gauge",
+                    MetricType::Histogram => "histogram",
+                    MetricType::Summary => "summary",
+                };
+                output.push_str(&format!("# TYPE {} {}\n", metric.name, type_str));
+            }
+        }
         for metric in metrics {
             let labels_str = if metric.labels.is_empty() {
                 String::new()
@@ -208,38 +562,85 @@ impl MetricsCollector {
                     .collect();
                 format!("{{{}}}", label_pairs.join(","))
             };
-            
+
             output.push_str(&format!("{}{} {}\n", metric.name, labels_str, metric.value));
         }
-        
+
         // Export histograms
         let histograms = self.get_histograms().await;
         for histogram in histograms {
-            let labels_str = if histogram.name.contains("query_type") {
-                let query_type = histogram.name.split('_').last().unwrap_or("unknown");
-                format!("{{query_type=\"{}\"}}", query_type)
-            } else {
+            let label_pairs: Vec<String> = histogram
+                .labels
+                .iter()
+                .map(|(k, v)| format!("{}=\"{}\"", k, v))
+                .collect();
+            let labels_str = if label_pairs.is_empty() {
                 String::new()
+            } else {
+                format!("{{{}}}", label_pairs.join(","))
             };
-            
+
+            if let Some(help) = descriptions.get(&histogram.name) {
+                output.push_str(&format!("# HELP {} {}\n", histogram.name, help));
+            }
             output.push_str(&format!("# HELP {}_sum Total sum of observed values\n", histogram.name));
             output.push_str(&format!("# TYPE {}_sum counter\n", histogram.name));
             output.push_str(&format!("{}_sum{} {}\n", histogram.name, labels_str, histogram.sum));
-            
+
             output.push_str(&format!("# HELP {}_count Total count of observed values\n", histogram.name));
             output.push_str(&format!("# TYPE {}_count counter\n", histogram.name));
             output.push_str(&format!("{}_count{} {}\n", histogram.name, labels_str, histogram.count));
             
             for bucket in &histogram.buckets {
-                let bucket_labels = if bucket.le == f64::INFINITY {
-                    format!("{}le=\"+Inf\"", labels_str)
+                let le = if bucket.le == f64::INFINITY {
+                    "+Inf".to_string()
                 } else {
-                    format!("{}le=\"{}\"", labels_str, bucket.le)
+                    bucket.le.to_string()
                 };
-                output.push_str(&format!("{}_bucket{} {}\n", histogram.name, bucket_labels, bucket.count));
+                let mut bucket_label_pairs = label_pairs.clone();
+                bucket_label_pairs.push(format!("le=\"{}\"", le));
+                output.push_str(&format!(
+                    "{}_bucket{{{}}} {}\n",
+                    histogram.name,
+                    bucket_label_pairs.join(","),
+                    bucket.count
+                ));
             }
         }
-        
+
+        // Export summaries
+        let summaries = self.get_summaries().await;
+        for summary in summaries {
+            let label_pairs: Vec<String> = summary
+                .labels
+                .iter()
+                .map(|(k, v)| format!("{}=\"{}\"", k, v))
+                .collect();
+            let base_labels_str = if label_pairs.is_empty() {
+                String::new()
+            } else {
+                format!("{{{}}}", label_pairs.join(","))
+            };
+
+            if let Some(help) = descriptions.get(&summary.name) {
+                output.push_str(&format!("# HELP {} {}\n", summary.name, help));
+            }
+            output.push_str(&format!("# HELP {}_sum Total sum of observed values\n", summary.name));
+            output.push_str(&format!("# TYPE {}_sum counter\n", summary.name));
+            output.push_str(&format!("{}_sum{} {}\n", summary.name, base_labels_str, summary.sum));
+
+            output.push_str(&format!("# HELP {}_count Total count of observed values\n", summary.name));
+            output.push_str(&format!("# TYPE {}_count counter\n", summary.name));
+            output.push_str(&format!("{}_count{} {}\n", summary.name, base_labels_str, summary.count));
+
+            output.push_str(&format!("# TYPE {} summary\n", summary.name));
+            for quantile_value in &summary.quantile_values {
+                let mut labels = label_pairs.clone();
+                labels.push(format!("quantile=\"{}\"", quantile_value.quantile));
+                output.push_str(&format!("{}{{{}}} {}\n", summary.name, labels.join(","), quantile_value.value));
+            }
+        }
+
         output
     }
     
@@ -271,6 +672,80 @@ impl Default for MetricsCollector {
     }
 }
 
+/// Collects the `Metric`s that named `Cache<T>` instances hand back from
+/// `Cache::metrics()` so they can be scraped over the same Prometheus text
+/// exposition format as the rest of the engine, the way Garage's admin
+/// `metrics.rs` aggregates per-subsystem counters behind one endpoint.
+///
+/// Kept separate from `MetricsCollector` because caches report pre-computed
+/// gauges/counters for a point-in-time snapshot (`Cache::get_stats`) rather
+/// than incrementing counters as events happen.
+pub struct CacheMetricsRegistry {
+    metrics: Arc<RwLock<HashMap<String, Metric>>>,
+}
+
+impl CacheMetricsRegistry {
+    pub fn new() -> Self {
+        Self {
+            metrics: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Replace the latest snapshot for whichever cache produced `metrics`
+    /// (its name is carried in each `Metric`'s `cache` label).
+    pub async fn record(&self, metrics: Vec<Metric>) {
+        let mut stored = self.metrics.write().await;
+        for metric in metrics {
+            let cache_name = metric.labels.get("cache").cloned().unwrap_or_default();
+            let key = format!("{}_{}", metric.name, cache_name);
+            stored.insert(key, metric);
+        }
+    }
+
+    pub async fn export_prometheus(&self) -> String {
+        let stored = self.metrics.read().await;
+
+        let mut by_name: HashMap<&str, Vec<&Metric>> = HashMap::new();
+        for metric in stored.values() {
+            by_name.entry(metric.name.as_str()).or_default().push(metric);
+        }
+
+        let mut output = String::new();
+        for (name, metrics) in by_name {
+            let type_str = match metrics[0].metric_type {
+                MetricType::Counter => "counter",
+                MetricType::Gauge => "gauge",
+                MetricType::Histogram => "histogram",
+                MetricType::Summary => "summary",
+            };
+            output.push_str(&format!("# TYPE {} {}\n", name, type_str));
+
+            for metric in metrics {
+                let labels_str = if metric.labels.is_empty() {
+                    String::new()
+                } else {
+                    let mut label_pairs: Vec<String> = metric
+                        .labels
+                        .iter()
+                        .map(|(k, v)| format!("{}=\"{}\"", k, v))
+                        .collect();
+                    label_pairs.sort();
+                    format!("{{{}}}", label_pairs.join(","))
+                };
+                output.push_str(&format!("{}{} {}\n", name, labels_str, metric.value));
+            }
+        }
+
+        output
+    }
+}
+
+impl Default for CacheMetricsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -315,5 +790,115 @@ mod tests {
         
         assert_eq!(histogram.sum, 4.0);
         assert_eq!(histogram.count, 2);
+
+        // No explicit registration - falls back to the Prometheus defaults.
+        assert_eq!(histogram.buckets.len(), DEFAULT_HISTOGRAM_BUCKETS.len() + 1);
+    }
+
+    #[tokio::test]
+    async fn test_registered_histogram_buckets_override_default() {
+        let collector = MetricsCollector::new();
+
+        collector
+            .register_histogram_buckets("fast_op_seconds", vec![0.001, 0.002, 0.005])
+            .await;
+        collector.observe_histogram("fast_op_seconds", 0.0015, None).await;
+
+        let histograms = collector.get_histograms().await;
+        let histogram = histograms.iter().find(|h| h.name == "fast_op_seconds").unwrap();
+
+        // 3 registered boundaries plus the implicit +Inf bucket.
+        assert_eq!(histogram.buckets.len(), 4);
+        assert_eq!(histogram.buckets[0].le, 0.001);
+        assert_eq!(histogram.buckets[0].count, 0);
+        assert_eq!(histogram.buckets[1].le, 0.002);
+        assert_eq!(histogram.buckets[1].count, 1);
+    }
+
+    #[test]
+    fn test_ddsketch_quantile_within_relative_error() {
+        let mut sketch = DDSketch::new(0.01);
+        for v in 1..=1000 {
+            sketch.observe(v as f64);
+        }
+
+        let p50 = sketch.quantile(0.5);
+        let p99 = sketch.quantile(0.99);
+
+        assert!((p50 - 500.0).abs() / 500.0 <= 0.02);
+        assert!((p99 - 990.0).abs() / 990.0 <= 0.02);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_expired_drops_stale_series_even_without_writes() {
+        let collector = MetricsCollector::with_ttls(Duration::from_millis(10), Duration::from_millis(10));
+
+        collector.increment_counter("stale_counter", None).await;
+        collector.observe_histogram("stale_histogram", 1.0, None).await;
+        assert_eq!(collector.get_metrics().await.len(), 1);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Nothing wrote to either series in the meantime - a read-only
+        // scrape still ages them out.
+        assert!(collector.get_metrics().await.is_empty());
+        assert!(collector.get_histograms().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sweep_expired_keeps_recently_observed_series() {
+        let collector = MetricsCollector::with_ttls(Duration::from_secs(300), Duration::from_secs(300));
+
+        collector.increment_counter("fresh_counter", None).await;
+        collector.sweep_expired().await;
+
+        assert_eq!(collector.get_metrics().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_observe_summary_exports_requested_quantiles() {
+        let collector = MetricsCollector::new();
+
+        for v in 1..=100 {
+            collector
+                .observe_summary("request_duration_seconds", v as f64, &[0.5, 0.9, 0.99], None)
+                .await;
+        }
+
+        let summaries = collector.get_summaries().await;
+        let summary = summaries.iter().find(|s| s.name == "request_duration_seconds").unwrap();
+
+        assert_eq!(summary.count, 100);
+        assert_eq!(summary.sum, (1..=100).sum::<i32>() as f64);
+        assert_eq!(summary.quantile_values.len(), 3);
+
+        let p99 = summary.quantile_values.iter().find(|qv| qv.quantile == 0.99).unwrap();
+        assert!((p99.value - 99.0).abs() / 99.0 <= 0.05);
+
+        let output = collector.export_prometheus().await;
+        assert!(output.contains("request_duration_seconds{quantile=\"0.99\"}"));
+        assert!(output.contains("request_duration_seconds_sum"));
+        assert!(output.contains("request_duration_seconds_count 100"));
+    }
+
+    #[tokio::test]
+    async fn test_cache_metrics_registry_exports_labeled_counters() {
+        let registry = CacheMetricsRegistry::new();
+
+        let mut labels = HashMap::new();
+        labels.insert("cache".to_string(), "query".to_string());
+        registry
+            .record(vec![Metric {
+                name: "vectra_cache_hits_total".to_string(),
+                value: 3.0,
+                timestamp: chrono::Utc::now(),
+                labels,
+                metric_type: MetricType::Counter,
+            }])
+            .await;
+
+        let output = registry.export_prometheus().await;
+        assert!(output.contains("# TYPE vectra_cache_hits_total counter"));
+        assert!(output.contains("vectra_cache_hits_total{cache=\"query\"} 3"));
     }
 }
@@ -0,0 +1,339 @@
+use std::sync::Arc;
+use std::time::Duration;
+use serde::Serialize;
+
+use crate::config::OtlpConfig;
+use crate::metrics::{HistogramBucket, MetricsCollector, Snapshot};
+
+/// OTLP's `AggregationTemporality::AGGREGATION_TEMPORALITY_CUMULATIVE`. Every
+/// series we export accumulates since process start (or since `sweep_expired`
+/// last dropped it), never resets mid-stream, so cumulative is the only
+/// temporality that matches what `MetricsCollector` actually tracks.
+const AGGREGATION_TEMPORALITY_CUMULATIVE: i32 = 2;
+
+#[derive(Debug, Serialize)]
+struct ExportMetricsServiceRequest {
+    #[serde(rename = "resourceMetrics")]
+    resource_metrics: Vec<ResourceMetrics>,
+}
+
+#[derive(Debug, Serialize)]
+struct ResourceMetrics {
+    resource: Resource,
+    #[serde(rename = "scopeMetrics")]
+    scope_metrics: Vec<ScopeMetrics>,
+}
+
+#[derive(Debug, Serialize)]
+struct Resource {
+    attributes: Vec<KeyValue>,
+}
+
+#[derive(Debug, Serialize)]
+struct ScopeMetrics {
+    scope: InstrumentationScope,
+    metrics: Vec<OtlpMetric>,
+}
+
+#[derive(Debug, Serialize)]
+struct InstrumentationScope {
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct KeyValue {
+    key: String,
+    value: AnyValue,
+}
+
+impl KeyValue {
+    fn string(key: &str, value: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            value: AnyValue { string_value: value.to_string() },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct AnyValue {
+    #[serde(rename = "stringValue")]
+    string_value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct OtlpMetric {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sum: Option<Sum>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gauge: Option<Gauge>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    histogram: Option<Histogram>,
+}
+
+#[derive(Debug, Serialize)]
+struct Sum {
+    #[serde(rename = "dataPoints")]
+    data_points: Vec<NumberDataPoint>,
+    #[serde(rename = "aggregationTemporality")]
+    aggregation_temporality: i32,
+    #[serde(rename = "isMonotonic")]
+    is_monotonic: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Gauge {
+    #[serde(rename = "dataPoints")]
+    data_points: Vec<NumberDataPoint>,
+}
+
+#[derive(Debug, Serialize)]
+struct NumberDataPoint {
+    attributes: Vec<KeyValue>,
+    #[serde(rename = "timeUnixNano")]
+    time_unix_nano: String,
+    #[serde(rename = "asDouble")]
+    as_double: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct Histogram {
+    #[serde(rename = "dataPoints")]
+    data_points: Vec<HistogramDataPoint>,
+    #[serde(rename = "aggregationTemporality")]
+    aggregation_temporality: i32,
+}
+
+#[derive(Debug, Serialize)]
+struct HistogramDataPoint {
+    attributes: Vec<KeyValue>,
+    #[serde(rename = "timeUnixNano")]
+    time_unix_nano: String,
+    count: u64,
+    sum: f64,
+    #[serde(rename = "bucketCounts")]
+    bucket_counts: Vec<u64>,
+    #[serde(rename = "explicitBounds")]
+    explicit_bounds: Vec<f64>,
+}
+
+/// `HistogramEntry::buckets` is cumulative (Prometheus' `le` semantics -
+/// each bucket counts every observation at or below its bound), but OTLP's
+/// `bucket_counts` wants the count that landed in each bucket on its own.
+fn cumulative_to_per_bucket(buckets: &[HistogramBucket]) -> Vec<u64> {
+    let mut result = Vec::with_capacity(buckets.len());
+    let mut previous = 0u64;
+    for bucket in buckets {
+        result.push(bucket.count.saturating_sub(previous));
+        previous = bucket.count;
+    }
+    result
+}
+
+fn build_export_request(snapshot: &Snapshot, config: &OtlpConfig) -> ExportMetricsServiceRequest {
+    let now_nanos = chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0).to_string();
+    let mut metrics = Vec::new();
+
+    for (name, entries) in &snapshot.counters {
+        let data_points = entries
+            .iter()
+            .map(|entry| NumberDataPoint {
+                attributes: entry.labels.iter().map(|(k, v)| KeyValue::string(k, v)).collect(),
+                time_unix_nano: now_nanos.clone(),
+                as_double: entry.value,
+            })
+            .collect();
+
+        metrics.push(OtlpMetric {
+            name: name.clone(),
+            sum: Some(Sum {
+                data_points,
+                aggregation_temporality: AGGREGATION_TEMPORALITY_CUMULATIVE,
+                is_monotonic: true,
+            }),
+            gauge: None,
+            histogram: None,
+        });
+    }
+
+    for (name, entries) in &snapshot.gauges {
+        let data_points = entries
+            .iter()
+            .map(|entry| NumberDataPoint {
+                attributes: entry.labels.iter().map(|(k, v)| KeyValue::string(k, v)).collect(),
+                time_unix_nano: now_nanos.clone(),
+                as_double: entry.value,
+            })
+            .collect();
+
+        metrics.push(OtlpMetric {
+            name: name.clone(),
+            sum: None,
+            gauge: Some(Gauge { data_points }),
+            histogram: None,
+        });
+    }
+
+    for (name, entries) in &snapshot.histograms {
+        let data_points = entries
+            .iter()
+            .map(|entry| HistogramDataPoint {
+                attributes: entry.labels.iter().map(|(k, v)| KeyValue::string(k, v)).collect(),
+                time_unix_nano: now_nanos.clone(),
+                count: entry.count,
+                sum: entry.sum,
+                bucket_counts: cumulative_to_per_bucket(&entry.buckets),
+                explicit_bounds: entry.buckets.iter().filter(|b| b.le.is_finite()).map(|b| b.le).collect(),
+            })
+            .collect();
+
+        metrics.push(OtlpMetric {
+            name: name.clone(),
+            sum: None,
+            gauge: None,
+            histogram: Some(Histogram {
+                data_points,
+                aggregation_temporality: AGGREGATION_TEMPORALITY_CUMULATIVE,
+            }),
+        });
+    }
+
+    ExportMetricsServiceRequest {
+        resource_metrics: vec![ResourceMetrics {
+            resource: Resource {
+                attributes: vec![
+                    KeyValue::string("service.name", &config.service_name),
+                    KeyValue::string("service.instance.id", &config.instance_id),
+                ],
+            },
+            scope_metrics: vec![ScopeMetrics {
+                scope: InstrumentationScope { name: "vectraedge".to_string() },
+                metrics,
+            }],
+        }],
+    }
+}
+
+/// Periodically pushes `MetricsCollector`'s counters/gauges/histograms to an
+/// OTLP collector over HTTP, for short-lived jobs and NAT'd environments
+/// where `export_prometheus`'s pull model doesn't reach. Counterpart to
+/// `metrics_endpoint`/`admin_stats` - those wait to be scraped, this pushes
+/// on its own `tokio::time::interval`.
+pub struct OtlpExporter;
+
+impl OtlpExporter {
+    /// Spawns the push loop as a background task and returns immediately. A
+    /// no-op (no task spawned) when `config.enabled` is false.
+    pub fn spawn(metrics: Arc<MetricsCollector>, config: OtlpConfig) {
+        if !config.enabled {
+            return;
+        }
+
+        tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            let url = format!("{}/v1/metrics", config.endpoint.trim_end_matches('/'));
+            let mut ticker = tokio::time::interval(Duration::from_secs(config.push_interval_secs.max(1)));
+
+            loop {
+                ticker.tick().await;
+
+                let snapshot = metrics.snapshot().await;
+                let request = build_export_request(&snapshot, &config);
+
+                if let Err(err) = client.post(&url).json(&request).send().await {
+                    tracing::warn!("failed to push OTLP metrics to {}: {}", url, err);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> OtlpConfig {
+        OtlpConfig {
+            enabled: true,
+            endpoint: "http://collector:4318".to_string(),
+            push_interval_secs: 15,
+            service_name: "vectraedge".to_string(),
+            instance_id: "test-instance".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_build_export_request_maps_counter_to_monotonic_sum() {
+        let collector = MetricsCollector::new();
+        collector.increment_counter("requests_total", None).await;
+
+        let snapshot = collector.snapshot().await;
+        let request = build_export_request(&snapshot, &test_config());
+
+        let metric = request.resource_metrics[0].scope_metrics[0]
+            .metrics
+            .iter()
+            .find(|m| m.name == "requests_total")
+            .unwrap();
+
+        let sum = metric.sum.as_ref().unwrap();
+        assert!(sum.is_monotonic);
+        assert_eq!(sum.data_points[0].as_double, 1.0);
+        assert!(metric.gauge.is_none());
+        assert!(metric.histogram.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_build_export_request_maps_gauge() {
+        let collector = MetricsCollector::new();
+        collector.set_gauge("queue_depth", 7.0, None).await;
+
+        let snapshot = collector.snapshot().await;
+        let request = build_export_request(&snapshot, &test_config());
+
+        let metric = request.resource_metrics[0].scope_metrics[0]
+            .metrics
+            .iter()
+            .find(|m| m.name == "queue_depth")
+            .unwrap();
+
+        assert_eq!(metric.gauge.as_ref().unwrap().data_points[0].as_double, 7.0);
+        assert!(metric.sum.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_build_export_request_converts_cumulative_buckets_to_per_bucket_counts() {
+        let collector = MetricsCollector::new();
+        collector.register_histogram_buckets("op_seconds", vec![0.1, 0.5, 1.0]).await;
+        collector.observe_histogram("op_seconds", 0.05, None).await;
+        collector.observe_histogram("op_seconds", 0.2, None).await;
+        collector.observe_histogram("op_seconds", 2.0, None).await;
+
+        let snapshot = collector.snapshot().await;
+        let request = build_export_request(&snapshot, &test_config());
+
+        let metric = request.resource_metrics[0].scope_metrics[0]
+            .metrics
+            .iter()
+            .find(|m| m.name == "op_seconds")
+            .unwrap();
+
+        let histogram = metric.histogram.as_ref().unwrap();
+        let data_point = &histogram.data_points[0];
+
+        assert_eq!(data_point.count, 3);
+        assert_eq!(data_point.explicit_bounds, vec![0.1, 0.5, 1.0]);
+        // One observation in (-inf, 0.1], one in (0.1, 0.5], none in (0.5, 1.0], one in (1.0, +inf).
+        assert_eq!(data_point.bucket_counts, vec![1, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_spawn_is_noop_when_disabled() {
+        let mut config = test_config();
+        config.enabled = false;
+        // Spawning with a disabled config must not panic or require a
+        // running Tokio reactor, since no task is actually spawned.
+        OtlpExporter::spawn(Arc::new(MetricsCollector::new()), config);
+    }
+}
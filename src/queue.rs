@@ -0,0 +1,308 @@
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::cache::Cache;
+use crate::embedding::{EmbeddingError, EmbeddingProvider};
+
+/// Ceiling on the exponential backoff applied when a rate-limited batch
+/// carries no `Retry-After` hint.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+struct QueueRequest {
+    text: String,
+    cache_key: String,
+    responder: oneshot::Sender<Result<Vec<f32>>>,
+}
+
+/// Batches `AIRuntime::generate_embedding` calls in front of a single
+/// `EmbeddingProvider`, so bulk inserts pay for a handful of provider
+/// round-trips instead of one per text.
+///
+/// Requests accumulate on a debounce timer, get grouped into batches whose
+/// combined token count stays under the provider's `max_input_tokens`
+/// (truncating any single text that alone exceeds the budget), and are
+/// flushed together. A batch that comes back rate-limited is retried whole,
+/// honoring the provider's `Retry-After` delay when given one and falling
+/// back to exponential backoff otherwise. A successful batch is written into
+/// the embedding cache before any of its waiters are woken.
+pub struct EmbeddingQueue {
+    sender: mpsc::UnboundedSender<QueueRequest>,
+}
+
+impl EmbeddingQueue {
+    pub fn spawn(
+        provider: Arc<dyn EmbeddingProvider>,
+        cache: Arc<Cache<Vec<f32>>>,
+        debounce: Duration,
+    ) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(Self::run(provider, cache, receiver, debounce));
+        Self { sender }
+    }
+
+    /// Enqueues `text` (cached under `cache_key`) and waits for its batch to
+    /// be flushed.
+    pub async fn submit(&self, text: String, cache_key: String) -> Result<Vec<f32>> {
+        let (responder, receiver) = oneshot::channel();
+        self.sender
+            .send(QueueRequest { text, cache_key, responder })
+            .map_err(|_| anyhow!("embedding queue worker is no longer running"))?;
+        receiver.await.map_err(|_| anyhow!("embedding queue dropped this request without a response"))?
+    }
+
+    async fn run(
+        provider: Arc<dyn EmbeddingProvider>,
+        cache: Arc<Cache<Vec<f32>>>,
+        mut receiver: mpsc::UnboundedReceiver<QueueRequest>,
+        debounce: Duration,
+    ) {
+        while let Some(first) = receiver.recv().await {
+            let mut pending = vec![first];
+
+            let deadline = tokio::time::sleep(debounce);
+            tokio::pin!(deadline);
+            loop {
+                tokio::select! {
+                    _ = &mut deadline => break,
+                    item = receiver.recv() => {
+                        match item {
+                            Some(item) => pending.push(item),
+                            None => break,
+                        }
+                    }
+                }
+            }
+
+            for batch in group_into_batches(pending, provider.max_input_tokens()) {
+                Self::flush_batch(&provider, &cache, batch).await;
+            }
+        }
+    }
+
+    async fn flush_batch(
+        provider: &Arc<dyn EmbeddingProvider>,
+        cache: &Arc<Cache<Vec<f32>>>,
+        batch: Vec<QueueRequest>,
+    ) {
+        let texts: Vec<String> = batch.iter().map(|item| item.text.clone()).collect();
+
+        let mut attempt: u32 = 0;
+        let embeddings = loop {
+            match provider.embed(&texts).await {
+                Ok(embeddings) => break embeddings,
+                Err(err) => {
+                    if let Some(EmbeddingError::RateLimited { retry_after }) = err.downcast_ref::<EmbeddingError>() {
+                        let delay = retry_after.unwrap_or_else(|| backoff_delay(attempt));
+                        attempt += 1;
+                        tracing::warn!(
+                            "embedding provider rate-limited a batch of {}, retrying in {:?}",
+                            texts.len(),
+                            delay
+                        );
+                        tokio::time::sleep(delay).await;
+                        continue;
+                    }
+
+                    for item in batch {
+                        let _ = item.responder.send(Err(anyhow!("embedding batch failed: {}", err)));
+                    }
+                    return;
+                }
+            }
+        };
+
+        if embeddings.len() != batch.len() {
+            let got = embeddings.len();
+            let want = batch.len();
+            for item in batch {
+                let _ = item.responder.send(Err(anyhow!(
+                    "embedding provider returned {} vectors for a batch of {}",
+                    got,
+                    want
+                )));
+            }
+            return;
+        }
+
+        // Write the whole batch into the cache before waking any waiter.
+        for (item, embedding) in batch.iter().zip(embeddings.iter()) {
+            let _ = cache.set(item.cache_key.clone(), embedding.clone()).await;
+        }
+
+        for (item, embedding) in batch.into_iter().zip(embeddings.into_iter()) {
+            let _ = item.responder.send(Ok(embedding));
+        }
+    }
+}
+
+fn backoff_delay(attempt: u32) -> Duration {
+    let delay = Duration::from_millis(500).saturating_mul(1u32 << attempt.min(6));
+    delay.min(MAX_BACKOFF)
+}
+
+/// Rough token estimate (~4 characters per token) used purely for batch
+/// budgeting - good enough to stay under a provider's `max_input_tokens`
+/// without needing the provider's own tokenizer on this path.
+fn estimate_tokens(text: &str) -> usize {
+    (text.len() / 4).max(1)
+}
+
+fn truncate_to_token_budget(text: &str, max_tokens: usize) -> String {
+    let max_chars = max_tokens.saturating_mul(4);
+    if text.len() <= max_chars {
+        text.to_string()
+    } else {
+        text.chars().take(max_chars).collect()
+    }
+}
+
+/// Groups `items` into batches whose combined estimated token count stays
+/// under `max_input_tokens`, truncating any single text that alone exceeds
+/// the budget rather than letting the provider reject it.
+fn group_into_batches(items: Vec<QueueRequest>, max_input_tokens: usize) -> Vec<Vec<QueueRequest>> {
+    let mut batches = Vec::new();
+    let mut current: Vec<QueueRequest> = Vec::new();
+    let mut current_tokens = 0usize;
+
+    for mut item in items {
+        if estimate_tokens(&item.text) > max_input_tokens {
+            item.text = truncate_to_token_budget(&item.text, max_input_tokens);
+        }
+
+        let tokens = estimate_tokens(&item.text);
+        if !current.is_empty() && current_tokens + tokens > max_input_tokens {
+            batches.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+
+        current_tokens += tokens;
+        current.push(item);
+    }
+
+    if !current.is_empty() {
+        batches.push(current);
+    }
+
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheConfig;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    fn test_cache() -> Arc<Cache<Vec<f32>>> {
+        Arc::new(Cache::new(CacheConfig::default()))
+    }
+
+    struct RecordingProvider {
+        batch_sizes: Mutex<Vec<usize>>,
+        max_input_tokens: usize,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for RecordingProvider {
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            self.batch_sizes.lock().unwrap().push(texts.len());
+            Ok(texts.iter().map(|t| vec![t.len() as f32]).collect())
+        }
+
+        fn dimensions(&self) -> usize {
+            1
+        }
+
+        fn max_input_tokens(&self) -> usize {
+            self.max_input_tokens
+        }
+    }
+
+    struct FlakyProvider {
+        attempts: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for FlakyProvider {
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(EmbeddingError::RateLimited { retry_after: Some(Duration::from_millis(5)) }.into());
+            }
+            Ok(texts.iter().map(|_| vec![1.0]).collect())
+        }
+
+        fn dimensions(&self) -> usize {
+            1
+        }
+
+        fn max_input_tokens(&self) -> usize {
+            8192
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_returns_embedding_and_populates_cache() {
+        let provider: Arc<dyn EmbeddingProvider> = Arc::new(RecordingProvider {
+            batch_sizes: Mutex::new(Vec::new()),
+            max_input_tokens: 8192,
+        });
+        let cache = test_cache();
+        let queue = EmbeddingQueue::spawn(provider, cache.clone(), Duration::from_millis(5));
+
+        let embedding = queue.submit("hello".to_string(), "embedding:hello".to_string()).await.unwrap();
+        assert_eq!(embedding, vec![5.0]);
+        assert_eq!(cache.get("embedding:hello").await, Some(vec![5.0]));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_submits_within_debounce_window_batch_together() {
+        let provider = Arc::new(RecordingProvider {
+            batch_sizes: Mutex::new(Vec::new()),
+            max_input_tokens: 8192,
+        });
+        let cache = test_cache();
+        let queue = Arc::new(EmbeddingQueue::spawn(provider.clone(), cache, Duration::from_millis(50)));
+
+        let mut handles = Vec::new();
+        for i in 0..5 {
+            let queue = queue.clone();
+            handles.push(tokio::spawn(async move {
+                queue.submit(format!("text-{}", i), format!("embedding:text-{}", i)).await.unwrap()
+            }));
+        }
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(provider.batch_sizes.lock().unwrap().iter().sum::<usize>(), 5);
+        assert!(provider.batch_sizes.lock().unwrap().len() < 5, "requests issued concurrently should batch");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_text_is_truncated_instead_of_rejected() {
+        let provider: Arc<dyn EmbeddingProvider> = Arc::new(RecordingProvider {
+            batch_sizes: Mutex::new(Vec::new()),
+            max_input_tokens: 4,
+        });
+        let cache = test_cache();
+        let queue = EmbeddingQueue::spawn(provider, cache.clone(), Duration::from_millis(5));
+
+        let huge_text = "x".repeat(1000);
+        let embedding = queue.submit(huge_text, "embedding:huge".to_string()).await.unwrap();
+        // max_input_tokens=4 -> 16 chars, so the recorded "embedding" (text.len()) should be truncated.
+        assert_eq!(embedding, vec![16.0]);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_batch_is_retried_and_eventually_succeeds() {
+        let provider: Arc<dyn EmbeddingProvider> = Arc::new(FlakyProvider { attempts: AtomicUsize::new(0) });
+        let cache = test_cache();
+        let queue = EmbeddingQueue::spawn(provider, cache, Duration::from_millis(5));
+
+        let embedding = queue.submit("hello".to_string(), "embedding:hello".to_string()).await.unwrap();
+        assert_eq!(embedding, vec![1.0]);
+    }
+}
@@ -0,0 +1,200 @@
+use std::ops::Range;
+
+/// Separators tried in priority order when a piece of text is still larger
+/// than `chunk_size`: paragraph breaks first, then sentence boundaries, then
+/// word boundaries, before falling back to a hard cut.
+const SEPARATORS: &[&str] = &["\n\n", ". ", "! ", "? ", "\n", " "];
+
+#[derive(Debug, Clone, Copy)]
+pub struct SplitterParams {
+    /// Maximum size of a chunk, in bytes.
+    pub chunk_size: usize,
+    /// How many trailing bytes of one chunk are repeated at the start of the
+    /// next, so a passage spanning a chunk boundary still has its context.
+    pub chunk_overlap: usize,
+}
+
+/// Splits `text` into overlapping chunks no larger than `params.chunk_size`
+/// bytes, recursively preferring paragraph, then sentence, then word
+/// boundaries over a hard cut. Returns each chunk's byte range into `text`.
+pub fn split_text(text: &str, params: &SplitterParams) -> Vec<Range<usize>> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    let pieces = split_recursive(text, 0..text.len(), 0, params.chunk_size);
+    merge_with_overlap(text, pieces, params)
+}
+
+/// Thin wrapper around `split_text` that pins a single `SplitterParams`, so
+/// `AIRuntime` doesn't have to thread chunk size/overlap through every call.
+pub struct TextSplitter {
+    params: SplitterParams,
+}
+
+impl TextSplitter {
+    pub fn new(params: SplitterParams) -> Self {
+        Self { params }
+    }
+
+    pub fn split(&self, text: &str) -> Vec<Range<usize>> {
+        split_text(text, &self.params)
+    }
+}
+
+/// Recursively breaks `range` into pieces no larger than `chunk_size`,
+/// trying each separator in turn before falling back to a hard cut on a
+/// character boundary.
+fn split_recursive(text: &str, range: Range<usize>, separator_index: usize, chunk_size: usize) -> Vec<Range<usize>> {
+    if range.len() <= chunk_size {
+        return vec![range];
+    }
+
+    let Some(separator) = SEPARATORS.get(separator_index) else {
+        return hard_split(text, range, chunk_size);
+    };
+
+    let slice = &text[range.clone()];
+    if !slice.contains(separator) {
+        return split_recursive(text, range, separator_index + 1, chunk_size);
+    }
+
+    let mut pieces = Vec::new();
+    let mut offset = range.start;
+    for part in slice.split_inclusive(separator) {
+        let part_range = offset..offset + part.len();
+        offset += part.len();
+        pieces.extend(split_recursive(text, part_range, separator_index + 1, chunk_size));
+    }
+    pieces
+}
+
+/// Last-resort split for a piece with no usable separator: cut every
+/// `chunk_size` bytes, rounded back to the nearest character boundary.
+fn hard_split(text: &str, range: Range<usize>, chunk_size: usize) -> Vec<Range<usize>> {
+    let mut pieces = Vec::new();
+    let mut start = range.start;
+    while start < range.end {
+        let mut end = (start + chunk_size).min(range.end);
+        while end > start && !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        pieces.push(start..end);
+        start = end;
+    }
+    pieces
+}
+
+/// Greedily packs atomic `pieces` into chunks up to `chunk_size`, then
+/// re-opens each new chunk `chunk_overlap` bytes before the previous one
+/// ended, so consecutive chunks share trailing/leading context.
+fn merge_with_overlap(text: &str, pieces: Vec<Range<usize>>, params: &SplitterParams) -> Vec<Range<usize>> {
+    let mut chunks = Vec::new();
+    let mut current: Option<Range<usize>> = None;
+
+    for piece in pieces {
+        current = Some(match current {
+            None => piece,
+            Some(open) if piece.end - open.start <= params.chunk_size => open.start..piece.end,
+            Some(open) => {
+                chunks.push(open.clone());
+                let overlap_start = char_boundary_at_or_after(text, open.end.saturating_sub(params.chunk_overlap));
+                let candidate_start = overlap_start.min(piece.start);
+                // `piece` alone can already be `chunk_size` bytes (e.g. a
+                // separator-free run that `hard_split` cut to the budget),
+                // so reopening `chunk_overlap` bytes before it would blow
+                // past `chunk_size`. Clamp the start forward so this chunk
+                // never exceeds the budget, even if that shrinks the
+                // overlap.
+                let min_start = char_boundary_at_or_after(text, piece.end.saturating_sub(params.chunk_size));
+                candidate_start.max(min_start)..piece.end
+            }
+        });
+    }
+
+    if let Some(open) = current {
+        chunks.push(open);
+    }
+
+    chunks
+}
+
+fn char_boundary_at_or_after(text: &str, mut index: usize) -> usize {
+    while index < text.len() && !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunks_of<'a>(text: &'a str, ranges: &[Range<usize>]) -> Vec<&'a str> {
+        ranges.iter().map(|r| &text[r.clone()]).collect()
+    }
+
+    #[test]
+    fn test_short_text_is_a_single_chunk() {
+        let text = "hello world";
+        let params = SplitterParams { chunk_size: 100, chunk_overlap: 10 };
+        let chunks = split_text(text, &params);
+        assert_eq!(chunks_of(text, &chunks), vec!["hello world"]);
+    }
+
+    #[test]
+    fn test_splits_on_paragraph_boundaries_before_hard_cutting() {
+        let text = "Paragraph one is here.\n\nParagraph two is here.\n\nParagraph three is here.";
+        let params = SplitterParams { chunk_size: 40, chunk_overlap: 0 };
+        let chunks = split_text(text, &params);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.len() <= 40);
+        }
+    }
+
+    #[test]
+    fn test_chunks_cover_the_whole_text_contiguously() {
+        let text = "one two three four five six seven eight nine ten";
+        let params = SplitterParams { chunk_size: 15, chunk_overlap: 5 };
+        let chunks = split_text(text, &params);
+
+        assert_eq!(chunks.first().unwrap().start, 0);
+        assert_eq!(chunks.last().unwrap().end, text.len());
+    }
+
+    #[test]
+    fn test_adjacent_chunks_overlap() {
+        let text = "one two three four five six seven eight nine ten";
+        let params = SplitterParams { chunk_size: 15, chunk_overlap: 5 };
+        let chunks = split_text(text, &params);
+
+        for window in chunks.windows(2) {
+            assert!(window[1].start < window[0].end, "next chunk should start before the previous one ends");
+        }
+    }
+
+    #[test]
+    fn test_hard_splits_a_single_word_longer_than_chunk_size() {
+        let text = "a".repeat(50);
+        let params = SplitterParams { chunk_size: 10, chunk_overlap: 0 };
+        let chunks = split_text(&text, &params);
+
+        assert!(chunks.iter().all(|c| c.len() <= 10));
+        assert_eq!(chunks.last().unwrap().end, text.len());
+    }
+
+    #[test]
+    fn test_hard_split_with_overlap_never_exceeds_chunk_size() {
+        // A separator-free run hard-split to exactly chunk_size can't also
+        // fit chunk_overlap bytes of context reopened before it - the merge
+        // step must clamp rather than grow the chunk past the budget.
+        let text = "a".repeat(40);
+        let params = SplitterParams { chunk_size: 20, chunk_overlap: 15 };
+        let chunks = split_text(&text, &params);
+
+        assert!(chunks.iter().all(|c| c.len() <= 20), "chunks: {:?}", chunks);
+        assert_eq!(chunks.last().unwrap().end, text.len());
+    }
+}
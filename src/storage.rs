@@ -1,236 +1,463 @@
-use std::sync::Arc;
 use tokio::sync::RwLock;
 use anyhow::Result;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::path::Path;
+use std::sync::Arc;
 
+use crate::chunking::{ChunkManifest, ChunkStore, ChunkingParams};
 use crate::config::Config;
+use crate::error::StorageError;
+use crate::lww::{HybridLogicalClock, LwwValue};
+use crate::merkle::MerkleIndex;
+use crate::storage_backend::{self, StorageBackend, StorageBackendKind};
+
+const METADATA_TREE: &str = "metadata";
+const COUNTERS_TREE: &str = "counters";
+const TABLE_LIST_KEY: &str = "table_list";
 
 pub struct StorageManager {
     config: Config,
-    rocksdb: Option<Arc<rocksdb::DB>>,
-    sled_db: Option<Arc<sled::Db>>,
+    backend: Box<dyn StorageBackend>,
     tables: Arc<RwLock<HashMap<String, TableMetadata>>>,
+    clock: HybridLogicalClock,
 }
 
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct TableMetadata {
     pub name: String,
     pub schema: String,
     pub row_count: u64,
     pub size_bytes: u64,
+    pub max_rows: Option<u64>,
+    pub max_size_bytes: Option<u64>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub last_modified: chrono::DateTime<chrono::Utc>,
 }
 
 impl StorageManager {
     pub async fn new(config: &Config) -> Result<Self> {
-        let mut manager = Self {
+        let backend = Self::open_configured_backend(config)?;
+
+        let manager = Self {
             config: config.clone(),
-            rocksdb: None,
-            sled_db: None,
+            backend,
             tables: Arc::new(RwLock::new(HashMap::new())),
+            clock: HybridLogicalClock::new(),
         };
-        
-        // Initialize storage backends
-        manager.initialize_storage().await?;
-        
+
+        manager.reload_tables().await?;
+
         Ok(manager)
     }
-    
-    async fn initialize_storage(&mut self) -> Result<()> {
-        // Initialize RocksDB if configured
-        if let Some(rocksdb_path) = &self.config.storage.rocksdb_path {
-            let db = rocksdb::DB::open_default(rocksdb_path)?;
-            self.rocksdb = Some(Arc::new(db));
-            tracing::info!("RocksDB initialized at {}", rocksdb_path);
-        }
-        
-        // Initialize Sled if configured
-        if let Some(sled_path) = &self.config.storage.sled_path {
-            let db = sled::open(sled_path)?;
-            self.sled_db = Some(Arc::new(db));
-            tracing::info!("Sled initialized at {}", sled_path);
+
+    /// Repopulate the in-memory table map from persisted metadata and
+    /// counters, so quotas and stats survive a restart.
+    async fn reload_tables(&self) -> Result<()> {
+        let table_names = self.read_table_list()?;
+        let mut tables = self.tables.write().await;
+
+        for name in table_names {
+            let key = format!("table:{}", name);
+            if let Some(bytes) = self.backend.get(METADATA_TREE, key.as_bytes())? {
+                if let Ok(mut metadata) = serde_json::from_slice::<TableMetadata>(&bytes) {
+                    metadata.row_count = self.read_counter(&Self::rows_key(&name))?;
+                    metadata.size_bytes = self.read_counter(&Self::bytes_key(&name))?;
+                    tables.insert(name, metadata);
+                }
+            }
         }
-        
+
         Ok(())
     }
-    
-    pub async fn create_table(&self, name: &str, schema: &str) -> Result<()> {
+
+    fn open_configured_backend(config: &Config) -> Result<Box<dyn StorageBackend>> {
+        let path = match config.storage.backend {
+            StorageBackendKind::RocksDb => config.storage.rocksdb_path.as_deref(),
+            StorageBackendKind::Sled => config.storage.sled_path.as_deref(),
+            StorageBackendKind::Lmdb => config.storage.lmdb_path.as_deref(),
+            StorageBackendKind::Sqlite => config.storage.sqlite_path.as_deref(),
+        }
+        .ok_or_else(|| anyhow::anyhow!("no path configured for storage backend {:?}", config.storage.backend))?;
+
+        let backend = storage_backend::open_backend(config.storage.backend, path)?;
+        tracing::info!("Storage backend {:?} initialized at {}", config.storage.backend, path);
+        Ok(backend)
+    }
+
+    pub async fn create_table(
+        &self,
+        name: &str,
+        schema: &str,
+        max_rows: Option<u64>,
+        max_size_bytes: Option<u64>,
+    ) -> Result<()> {
         let mut tables = self.tables.write().await;
-        
+
         let metadata = TableMetadata {
             name: name.to_string(),
             schema: schema.to_string(),
-            row_count: 0,
-            size_bytes: 0,
+            row_count: self.read_counter(&Self::rows_key(name))?,
+            size_bytes: self.read_counter(&Self::bytes_key(name))?,
+            max_rows,
+            max_size_bytes,
             created_at: chrono::Utc::now(),
             last_modified: chrono::Utc::now(),
         };
-        
-        tables.insert(name.to_string(), metadata);
-        
-        // Store table metadata in persistent storage
-        if let Some(rocksdb) = &self.rocksdb {
-            let key = format!("table:{}", name);
-            let value = serde_json::to_string(&metadata)?;
-            rocksdb.put(key.as_bytes(), value.as_bytes())?;
+
+        self.backend.open_tree(name)?;
+
+        let key = format!("table:{}", name);
+        let value = serde_json::to_string(&metadata)?;
+        self.backend.put(METADATA_TREE, key.as_bytes(), value.as_bytes())?;
+
+        let mut table_list = self.read_table_list()?;
+        if !table_list.iter().any(|t| t == name) {
+            table_list.push(name.to_string());
+            self.write_table_list(&table_list)?;
         }
-        
+
+        tables.insert(name.to_string(), metadata);
+
         tracing::info!("Created table: {}", name);
         Ok(())
     }
-    
+
     pub async fn drop_table(&self, name: &str) -> Result<()> {
         let mut tables = self.tables.write().await;
         tables.remove(name);
-        
-        // Remove from persistent storage
-        if let Some(rocksdb) = &self.rocksdb {
-            let key = format!("table:{}", name);
-            rocksdb.delete(key.as_bytes())?;
-        }
-        
+
+        let key = format!("table:{}", name);
+        self.backend.delete(METADATA_TREE, key.as_bytes())?;
+
+        let mut table_list = self.read_table_list()?;
+        table_list.retain(|t| t != name);
+        self.write_table_list(&table_list)?;
+
         tracing::info!("Dropped table: {}", name);
         Ok(())
     }
-    
+
     pub async fn insert_data(&self, table_name: &str, key: &str, value: &Value) -> Result<()> {
-        // Store data in RocksDB
-        if let Some(rocksdb) = &self.rocksdb {
-            let full_key = format!("{}:{}", table_name, key);
-            let value_bytes = serde_json::to_string(value)?.into_bytes();
-            rocksdb.put(full_key.as_bytes(), &value_bytes)?;
+        let timestamp = self.clock.tick();
+        self.merge_data(table_name, key, value.clone(), timestamp).await
+    }
+
+    /// Apply a write under last-writer-wins semantics: `timestamp` only
+    /// overwrites the existing entry if it compares greater (see
+    /// `LwwValue::should_replace`), so replication/repair can feed in
+    /// remote writes alongside local ones without racing them.
+    pub async fn merge_data(&self, table_name: &str, key: &str, value: Value, timestamp: u64) -> Result<()> {
+        self.clock.observe(timestamp);
+
+        let incoming = LwwValue::new(timestamp, value);
+        let existing = self.read_lww(table_name, key)?;
+        if !LwwValue::should_replace(existing.as_ref(), &incoming)? {
+            return Ok(());
         }
-        
-        // Store data in Sled for fast access
-        if let Some(sled_db) = &self.sled_db {
-            let tree = sled_db.open_tree(table_name)?;
-            let key_bytes = key.as_bytes();
-            let value_bytes = serde_json::to_string(value)?.into_bytes();
-            tree.insert(key_bytes, value_bytes)?;
+
+        let incoming_size = incoming.value.to_string().len() as u64;
+        let old_size = existing.as_ref().map(|lww| lww.value.to_string().len() as u64).unwrap_or(0);
+
+        // Hold the tables lock across the quota check and the stats commit
+        // below, instead of two independent lock acquisitions, so two
+        // concurrent merges on the same table can't both pass the check
+        // before either has committed its counters.
+        let mut tables = self.tables.write().await;
+        Self::check_quota_locked(&tables, table_name, existing.is_none(), old_size, incoming_size)?;
+
+        if let Some(existing_bytes) = self.backend.get(table_name, key.as_bytes())? {
+            if let Ok(manifest) = serde_json::from_slice::<ChunkManifest>(&existing_bytes) {
+                if manifest.is_chunked {
+                    ChunkStore::new(self.backend.as_ref()).release_value(&manifest.chunks)?;
+                }
+            }
         }
-        
-        // Update table metadata
-        self.update_table_stats(table_name, 1, value.to_string().len() as u64).await?;
-        
+
+        let lww_bytes = serde_json::to_vec(&incoming)?;
+        let stored_bytes = if self.chunking_enabled(table_name) && lww_bytes.len() >= self.chunking_params().min_chunk_size {
+            let chunks = ChunkStore::new(self.backend.as_ref()).store_value(&lww_bytes, &self.chunking_params())?;
+            serde_json::to_vec(&ChunkManifest { is_chunked: true, chunks })?
+        } else {
+            lww_bytes.clone()
+        };
+
+        self.backend.put(table_name, key.as_bytes(), &stored_bytes)?;
+        MerkleIndex::new(self.backend.as_ref()).update_leaf(table_name, key, Some(&lww_bytes))?;
+
+        let row_delta = if existing.is_none() { 1 } else { 0 };
+        let size_delta = incoming_size as i64 - old_size as i64;
+        self.commit_table_stats_locked(&mut tables, table_name, row_delta, size_delta)?;
+
         Ok(())
     }
-    
-    pub async fn get_data(&self, table_name: &str, key: &str) -> Result<Option<Value>> {
-        // Try Sled first (faster)
-        if let Some(sled_db) = &self.sled_db {
-            if let Ok(tree) = sled_db.open_tree(table_name) {
-                if let Ok(Some(value_bytes)) = tree.get(key.as_bytes()) {
-                    if let Ok(value) = serde_json::from_slice::<Value>(&value_bytes) {
-                        return Ok(Some(value));
-                    }
+
+    /// Row/size quota checks against an already-locked `tables` map so
+    /// the caller can hold the lock through to the stats commit and close
+    /// the check-then-commit race window. Checked against the *net* row/size
+    /// delta the write will actually commit (see `row_delta`/`size_delta` in
+    /// `merge_data`) rather than the gross incoming size, so a same-key
+    /// overwrite isn't double-counted against a table already at quota.
+    fn check_quota_locked(
+        tables: &HashMap<String, TableMetadata>,
+        table_name: &str,
+        is_new_row: bool,
+        old_size: u64,
+        incoming_size: u64,
+    ) -> Result<()> {
+        let Some(metadata) = tables.get(table_name) else {
+            return Ok(());
+        };
+
+        if let Some(max_rows) = metadata.max_rows {
+            let new_row_count = if is_new_row { metadata.row_count + 1 } else { metadata.row_count };
+            if new_row_count > max_rows {
+                return Err(StorageError::QuotaExceeded {
+                    table: table_name.to_string(),
+                    reason: format!("row count would exceed max_rows={}", max_rows),
                 }
+                .into());
             }
         }
-        
-        // Fallback to RocksDB
-        if let Some(rocksdb) = &self.rocksdb {
-            let full_key = format!("{}:{}", table_name, key);
-            if let Ok(Some(value_bytes)) = rocksdb.get(full_key.as_bytes()) {
-                if let Ok(value) = serde_json::from_slice::<Value>(&value_bytes) {
-                    return Ok(Some(value));
+
+        if let Some(max_size_bytes) = metadata.max_size_bytes {
+            let new_size_bytes = metadata.size_bytes as i64 + incoming_size as i64 - old_size as i64;
+            if new_size_bytes > max_size_bytes as i64 {
+                return Err(StorageError::QuotaExceeded {
+                    table: table_name.to_string(),
+                    reason: format!("size would exceed max_size_bytes={}", max_size_bytes),
                 }
+                .into());
             }
         }
-        
-        Ok(None)
+
+        Ok(())
+    }
+
+    pub async fn get_data(&self, table_name: &str, key: &str) -> Result<Option<Value>> {
+        Ok(self.read_lww(table_name, key)?.map(|lww| lww.value))
+    }
+
+    /// Fetch and decode the stored `LwwValue` for `key`, transparently
+    /// reassembling it from chunk storage if it was written chunked.
+    fn read_lww(&self, table_name: &str, key: &str) -> Result<Option<LwwValue>> {
+        let Some(stored_bytes) = self.backend.get(table_name, key.as_bytes())? else {
+            return Ok(None);
+        };
+
+        let lww_bytes = match serde_json::from_slice::<ChunkManifest>(&stored_bytes) {
+            Ok(manifest) if manifest.is_chunked => {
+                ChunkStore::new(self.backend.as_ref()).load_value(&manifest.chunks)?
+            }
+            _ => stored_bytes,
+        };
+
+        Ok(serde_json::from_slice::<LwwValue>(&lww_bytes).ok())
     }
-    
+
     pub async fn delete_data(&self, table_name: &str, key: &str) -> Result<()> {
-        // Remove from RocksDB
-        if let Some(rocksdb) = &self.rocksdb {
-            let full_key = format!("{}:{}", table_name, key);
-            rocksdb.delete(full_key.as_bytes())?;
-        }
-        
-        // Remove from Sled
-        if let Some(sled_db) = &self.sled_db {
-            if let Ok(tree) = sled_db.open_tree(table_name) {
-                tree.remove(key.as_bytes())?;
+        let old_size = self
+            .read_lww(table_name, key)?
+            .map(|lww| lww.value.to_string().len() as u64)
+            .unwrap_or(0);
+
+        if let Some(stored_bytes) = self.backend.get(table_name, key.as_bytes())? {
+            if let Ok(manifest) = serde_json::from_slice::<ChunkManifest>(&stored_bytes) {
+                if manifest.is_chunked {
+                    ChunkStore::new(self.backend.as_ref()).release_value(&manifest.chunks)?;
+                }
             }
         }
-        
-        // Update table metadata
-        self.update_table_stats(table_name, -1, 0).await?;
-        
+
+        self.backend.delete(table_name, key.as_bytes())?;
+        MerkleIndex::new(self.backend.as_ref()).update_leaf(table_name, key, None)?;
+
+        self.update_table_stats(table_name, -1, -(old_size as i64)).await?;
+
         Ok(())
     }
-    
+
+    fn chunking_enabled(&self, table_name: &str) -> bool {
+        self.config.storage.chunking.enabled_tables.iter().any(|t| t == table_name)
+    }
+
+    fn chunking_params(&self) -> ChunkingParams {
+        let chunking = &self.config.storage.chunking;
+        ChunkingParams {
+            min_chunk_size: chunking.min_chunk_size,
+            max_chunk_size: chunking.max_chunk_size,
+            target_chunk_size: chunking.target_chunk_size,
+        }
+    }
+
     pub async fn list_tables(&self) -> Result<Vec<TableMetadata>> {
         let tables = self.tables.read().await;
         Ok(tables.values().cloned().collect())
     }
-    
+
     pub async fn get_table_info(&self, name: &str) -> Result<Option<TableMetadata>> {
         let tables = self.tables.read().await;
         Ok(tables.get(name).cloned())
     }
-    
-    async fn update_table_stats(&self, table_name: &str, row_delta: i64, size_delta: u64) -> Result<()> {
+
+    /// Reads every row currently stored in `table_name`. Used by batch
+    /// consumers (e.g. scheduled re-embedding jobs) that need to see the
+    /// whole table rather than one key at a time.
+    pub async fn scan_table(&self, table_name: &str) -> Result<Vec<(String, Value)>> {
+        let mut rows = Vec::new();
+        for key_bytes in self.backend.list_keys(table_name)? {
+            let key = String::from_utf8_lossy(&key_bytes).into_owned();
+            if let Some(value) = self.get_data(table_name, &key).await? {
+                rows.push((key, value));
+            }
+        }
+        Ok(rows)
+    }
+
+    async fn update_table_stats(&self, table_name: &str, row_delta: i64, size_delta: i64) -> Result<()> {
         let mut tables = self.tables.write().await;
-        
+        self.commit_table_stats_locked(&mut tables, table_name, row_delta, size_delta)
+    }
+
+    /// Same bookkeeping as before, but against an already-locked `tables`
+    /// map so a caller (e.g. `merge_data`) can commit under the same guard
+    /// it ran its quota check with.
+    fn commit_table_stats_locked(
+        &self,
+        tables: &mut HashMap<String, TableMetadata>,
+        table_name: &str,
+        row_delta: i64,
+        size_delta: i64,
+    ) -> Result<()> {
+        let rows_key = Self::rows_key(table_name);
+        let bytes_key = Self::bytes_key(table_name);
+
+        let current_rows = self.read_counter(&rows_key)?;
+        let new_rows = if row_delta > 0 {
+            current_rows + row_delta as u64
+        } else {
+            current_rows.saturating_sub((-row_delta) as u64)
+        };
+
+        let current_bytes = self.read_counter(&bytes_key)?;
+        let new_bytes = if size_delta > 0 {
+            current_bytes.saturating_add(size_delta as u64)
+        } else {
+            current_bytes.saturating_sub((-size_delta) as u64)
+        };
+
+        self.write_counter(&rows_key, new_rows)?;
+        self.write_counter(&bytes_key, new_bytes)?;
+
         if let Some(table) = tables.get_mut(table_name) {
-            if row_delta > 0 {
-                table.row_count += row_delta as u64;
-            } else {
-                table.row_count = table.row_count.saturating_sub((-row_delta) as u64);
-            }
-            
-            table.size_bytes = table.size_bytes.saturating_add(size_delta);
+            table.row_count = new_rows;
+            table.size_bytes = new_bytes;
             table.last_modified = chrono::Utc::now();
         }
-        
+
+        Ok(())
+    }
+
+    fn rows_key(table_name: &str) -> String {
+        format!("rows:{}", table_name)
+    }
+
+    fn bytes_key(table_name: &str) -> String {
+        format!("bytes:{}", table_name)
+    }
+
+    fn read_counter(&self, key: &str) -> Result<u64> {
+        Ok(self
+            .backend
+            .get(COUNTERS_TREE, key.as_bytes())?
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(0))
+    }
+
+    fn write_counter(&self, key: &str, value: u64) -> Result<()> {
+        self.backend.put(COUNTERS_TREE, key.as_bytes(), value.to_string().as_bytes())
+    }
+
+    fn read_table_list(&self) -> Result<Vec<String>> {
+        Ok(self
+            .backend
+            .get(METADATA_TREE, TABLE_LIST_KEY.as_bytes())?
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default())
+    }
+
+    fn write_table_list(&self, table_list: &[String]) -> Result<()> {
+        self.backend
+            .put(METADATA_TREE, TABLE_LIST_KEY.as_bytes(), &serde_json::to_vec(table_list)?)
+    }
+
+    /// Rescan `table` from storage and rebuild its persisted row/byte
+    /// counters, for recovering after a crash left them out of sync.
+    pub async fn repair_counters(&self, table: &str) -> Result<()> {
+        let keys = self.backend.list_keys(table)?;
+
+        // Measure via `read_lww` (reassemble-then-measure), not raw backend
+        // bytes: for a chunking-enabled table the raw value is just the
+        // small `ChunkManifest`, not the decoded size that `merge_data`/
+        // `delete_data` account by.
+        let mut total_size = 0u64;
+        for key in &keys {
+            let key_str = String::from_utf8_lossy(key);
+            if let Some(lww) = self.read_lww(table, &key_str)? {
+                total_size += lww.value.to_string().len() as u64;
+            }
+        }
+        let row_count = keys.len() as u64;
+
+        self.write_counter(&Self::rows_key(table), row_count)?;
+        self.write_counter(&Self::bytes_key(table), total_size)?;
+
+        let mut tables = self.tables.write().await;
+        if let Some(metadata) = tables.get_mut(table) {
+            metadata.row_count = row_count;
+            metadata.size_bytes = total_size;
+            metadata.last_modified = chrono::Utc::now();
+        }
+
+        tracing::info!("Repaired counters for table {}: {} rows, {} bytes", table, row_count, total_size);
         Ok(())
     }
-    
+
     pub async fn get_storage_stats(&self) -> Result<Value> {
         let tables = self.tables.read().await;
         let total_tables = tables.len();
         let total_rows: u64 = tables.values().map(|t| t.row_count).sum();
         let total_size: u64 = tables.values().map(|t| t.size_bytes).sum();
-        
+
         Ok(serde_json::json!({
             "total_tables": total_tables,
             "total_rows": total_rows,
             "total_size_bytes": total_size,
-            "rocksdb_available": self.rocksdb.is_some(),
-            "sled_available": self.sled_db.is_some()
+            "backend": format!("{:?}", self.config.storage.backend)
         }))
     }
-    
-    pub async fn compact_storage(&self) -> Result<()> {
-        // Compact RocksDB
-        if let Some(rocksdb) = &self.rocksdb {
-            rocksdb.compact_range(None::<&[u8]>, None::<&[u8]>)?;
-            tracing::info!("RocksDB compaction completed");
-        }
-        
-        // Compact Sled
-        if let Some(sled_db) = &self.sled_db {
-            sled_db.flush()?;
-            tracing::info!("Sled flush completed");
-        }
-        
-        Ok(())
+
+    /// Hex-encoded root hash of `table`'s Merkle tree, for cheap equality
+    /// checks against a replica before paying for a full `diff_tables` walk.
+    pub async fn get_table_root_hash(&self, table: &str) -> Result<String> {
+        MerkleIndex::new(self.backend.as_ref()).root_hash(table)
     }
-}
 
-impl Clone for TableMetadata {
-    fn clone(&self) -> Self {
-        Self {
-            name: self.name.clone(),
-            schema: self.schema.clone(),
-            row_count: self.row_count,
-            size_bytes: self.size_bytes,
-            created_at: self.created_at,
-            last_modified: self.last_modified,
-        }
+    /// Row keys that diverge between this table and a peer, without scanning
+    /// either side in full. `other_root_fetch_fn` resolves a Merkle node path
+    /// to its hash on the peer being compared against.
+    pub async fn diff_tables<F>(&self, table: &str, other_root_fetch_fn: F) -> Result<Vec<String>>
+    where
+        F: Fn(&str) -> Result<[u8; 32]>,
+    {
+        MerkleIndex::new(self.backend.as_ref()).diff(table, other_root_fetch_fn)
+    }
+
+    pub async fn compact_storage(&self) -> Result<()> {
+        self.backend.flush()?;
+        self.backend.compact()?;
+        tracing::info!("Storage compaction completed");
+        Ok(())
     }
 }
 
@@ -238,19 +465,92 @@ impl Clone for TableMetadata {
 mod tests {
     use super::*;
     use tempfile::tempdir;
-    
+
     #[tokio::test]
     async fn test_create_table() {
         let mut config = Config::default();
         let temp_dir = tempdir().unwrap();
         config.storage.rocksdb_path = Some(temp_dir.path().join("rocksdb").to_string_lossy().to_string());
-        
+
         let manager = StorageManager::new(&config).await.unwrap();
-        
-        manager.create_table("test_table", "id INT, name TEXT").await.unwrap();
-        
+
+        manager.create_table("test_table", "id INT, name TEXT", None, None).await.unwrap();
+
         let tables = manager.list_tables().await.unwrap();
         assert_eq!(tables.len(), 1);
         assert_eq!(tables[0].name, "test_table");
     }
+
+    #[tokio::test]
+    async fn test_concurrent_writers_cannot_exceed_row_quota() {
+        // Quota check and stats commit used to happen under two independent
+        // lock acquisitions, so concurrent merges could all pass the check
+        // before any of them committed - letting the quota be exceeded.
+        let mut config = Config::default();
+        let temp_dir = tempdir().unwrap();
+        config.storage.rocksdb_path = Some(temp_dir.path().join("rocksdb").to_string_lossy().to_string());
+
+        let manager = Arc::new(StorageManager::new(&config).await.unwrap());
+        manager.create_table("quota_table", "id INT", Some(5), None).await.unwrap();
+
+        let mut handles = Vec::new();
+        for i in 0..20 {
+            let manager = Arc::clone(&manager);
+            handles.push(tokio::spawn(async move {
+                manager
+                    .insert_data("quota_table", &format!("key{}", i), &serde_json::json!({"i": i}))
+                    .await
+            }));
+        }
+
+        for handle in handles {
+            let _ = handle.await.unwrap();
+        }
+
+        let info = manager.get_table_info("quota_table").await.unwrap().unwrap();
+        assert!(info.row_count <= 5, "row_count {} exceeded max_rows=5", info.row_count);
+    }
+
+    #[tokio::test]
+    async fn test_overwrite_at_full_row_quota_is_allowed() {
+        // The quota precheck used to always add +1 to row_count, so a
+        // same-key overwrite on a table already sitting at max_rows was
+        // wrongly rejected even though it doesn't add a row.
+        let mut config = Config::default();
+        let temp_dir = tempdir().unwrap();
+        config.storage.rocksdb_path = Some(temp_dir.path().join("rocksdb").to_string_lossy().to_string());
+
+        let manager = StorageManager::new(&config).await.unwrap();
+        manager.create_table("quota_table", "id INT", Some(1), None).await.unwrap();
+
+        manager.insert_data("quota_table", "key1", &serde_json::json!({"i": 1})).await.unwrap();
+        manager.insert_data("quota_table", "key1", &serde_json::json!({"i": 2})).await.unwrap();
+
+        let info = manager.get_table_info("quota_table").await.unwrap().unwrap();
+        assert_eq!(info.row_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_overwrite_exceeding_size_quota_is_rejected() {
+        // The quota precheck used to add the gross incoming size to
+        // size_bytes instead of the net delta, so an overwrite that grows a
+        // row past max_size_bytes must still be rejected.
+        let mut config = Config::default();
+        let temp_dir = tempdir().unwrap();
+        config.storage.rocksdb_path = Some(temp_dir.path().join("rocksdb").to_string_lossy().to_string());
+
+        let manager = StorageManager::new(&config).await.unwrap();
+        let small_value = serde_json::json!({"i": 1});
+        let small_size = small_value.to_string().len() as u64;
+        manager.create_table("quota_table", "id INT", None, Some(small_size)).await.unwrap();
+
+        manager.insert_data("quota_table", "key1", &small_value).await.unwrap();
+
+        let big_value = serde_json::json!({"i": "much larger payload than before"});
+        let err = manager.insert_data("quota_table", "key1", &big_value).await.unwrap_err();
+        assert!(err.downcast_ref::<StorageError>().is_some());
+
+        let info = manager.get_table_info("quota_table").await.unwrap().unwrap();
+        assert_eq!(info.size_bytes, small_size);
+    }
 }
@@ -1,17 +1,27 @@
 use std::sync::Arc;
-use tokio::sync::{RwLock, mpsc};
+use tokio::sync::{Mutex, RwLock};
 use anyhow::Result;
 use serde_json::Value;
 use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::config::Config;
+use crate::storage::StorageManager;
+
+const OFFSETS_TABLE: &str = "stream_offsets";
+const COUNTERS_TABLE: &str = "stream_counters";
 
 pub struct StreamManager {
     config: Config,
+    storage: Arc<StorageManager>,
     subscriptions: Arc<RwLock<HashMap<String, StreamSubscription>>>,
     producers: Arc<RwLock<HashMap<String, StreamProducer>>>,
     consumers: Arc<RwLock<HashMap<String, StreamConsumer>>>,
+    /// One mutex per topic, held across the read-then-increment in
+    /// `next_offset` so two concurrent `publish` calls on the same topic
+    /// can't both observe the same counter value and overwrite each other's
+    /// message under the same `offset_key`.
+    offset_locks: Arc<RwLock<HashMap<String, Arc<Mutex<()>>>>>,
 }
 
 pub struct StreamSubscription {
@@ -33,58 +43,68 @@ pub struct StreamConsumer {
 }
 
 impl StreamManager {
-    pub async fn new(config: &Config) -> Result<Self> {
+    pub async fn new(config: &Config, storage: Arc<StorageManager>) -> Result<Self> {
         Ok(Self {
-            config,
+            config: config.clone(),
+            storage,
             subscriptions: Arc::new(RwLock::new(HashMap::new())),
             producers: Arc::new(RwLock::new(HashMap::new())),
             consumers: Arc::new(RwLock::new(HashMap::new())),
+            offset_locks: Arc::new(RwLock::new(HashMap::new())),
         })
     }
-    
+
     pub async fn subscribe(&self, topic: &str) -> Result<StreamSubscription> {
         let subscription_id = Uuid::new_v4().to_string();
-        
+
         let subscription = StreamSubscription {
             id: subscription_id.clone(),
             topic: topic.to_string(),
             status: "active".to_string(),
             created_at: chrono::Utc::now(),
         };
-        
+
+        // New subscriptions start at offset 0 so they can replay the full
+        // topic history; call `seek` afterwards to start from "latest".
+        self.storage.insert_data(OFFSETS_TABLE, &subscription_id, &serde_json::json!(0)).await?;
+
         // Store subscription
         let mut subscriptions = self.subscriptions.write().await;
         subscriptions.insert(subscription_id.clone(), subscription.clone());
-        
+
         // Create consumer for the topic
         let consumer = StreamConsumer {
             topic: topic.to_string(),
             subscription_id: subscription_id.clone(),
             status: "active".to_string(),
         };
-        
+
         let mut consumers = self.consumers.write().await;
         consumers.insert(subscription_id, consumer);
-        
+
         Ok(subscription)
     }
-    
+
     pub async fn unsubscribe(&self, subscription_id: &str) -> Result<()> {
         let mut subscriptions = self.subscriptions.write().await;
         subscriptions.remove(subscription_id);
-        
+
         let mut consumers = self.consumers.write().await;
         consumers.remove(subscription_id);
-        
+
+        self.storage.delete_data(OFFSETS_TABLE, subscription_id).await?;
+
         Ok(())
     }
-    
-    pub async fn publish(&self, topic: &str, message: Value) -> Result<()> {
-        // In a real implementation, this would publish to Redpanda
-        // For now, we'll just log the message
-        
-        tracing::info!("Publishing to topic {}: {:?}", topic, message);
-        
+
+    pub async fn publish(&self, topic: &str, message: Value) -> Result<u64> {
+        let offset = self.next_offset(topic).await?;
+        self.storage
+            .insert_data(&Self::log_table(topic), &Self::offset_key(offset), &message)
+            .await?;
+
+        tracing::info!("Published to topic {} at offset {}", topic, offset);
+
         // Store producer if it doesn't exist
         let mut producers = self.producers.write().await;
         if !producers.contains_key(topic) {
@@ -93,71 +113,183 @@ impl StreamManager {
                 status: "active".to_string(),
             });
         }
-        
+
+        Ok(offset)
+    }
+
+    /// Fetch up to `max` unread messages for `subscription_id`, starting
+    /// right after its last committed offset. Call `commit_offset` once the
+    /// batch has been processed so a reconnect resumes from here.
+    pub async fn poll(&self, subscription_id: &str, max: usize) -> Result<Vec<(u64, Value)>> {
+        let topic = {
+            let subscriptions = self.subscriptions.read().await;
+            subscriptions
+                .get(subscription_id)
+                .ok_or_else(|| anyhow::anyhow!("unknown subscription: {}", subscription_id))?
+                .topic
+                .clone()
+        };
+
+        let mut offset = self.committed_offset(subscription_id).await?;
+        let next_offset = self.peek_next_offset(&topic).await?;
+
+        let mut messages = Vec::new();
+        let log_table = Self::log_table(&topic);
+        while offset < next_offset && messages.len() < max {
+            if let Some(value) = self.storage.get_data(&log_table, &Self::offset_key(offset)).await? {
+                messages.push((offset, value));
+            }
+            offset += 1;
+        }
+
+        Ok(messages)
+    }
+
+    /// Durably record that everything up to and including `offset` has been
+    /// processed; the next `poll` starts at `offset + 1`.
+    pub async fn commit_offset(&self, subscription_id: &str, offset: u64) -> Result<()> {
+        self.storage
+            .insert_data(OFFSETS_TABLE, subscription_id, &serde_json::json!(offset + 1))
+            .await?;
         Ok(())
     }
-    
+
+    /// Move a subscription's read position to an arbitrary offset, e.g. to
+    /// replay history after a reconnect.
+    pub async fn seek(&self, subscription_id: &str, offset: u64) -> Result<()> {
+        self.storage
+            .insert_data(OFFSETS_TABLE, subscription_id, &serde_json::json!(offset))
+            .await?;
+        Ok(())
+    }
+
+    fn log_table(topic: &str) -> String {
+        format!("stream_log_{}", topic)
+    }
+
+    fn offset_key(offset: u64) -> String {
+        format!("{:020}", offset)
+    }
+
+    async fn next_offset(&self, topic: &str) -> Result<u64> {
+        let lock = self.topic_offset_lock(topic).await;
+        let _guard = lock.lock().await;
+
+        let current = self.peek_next_offset(topic).await?;
+        self.storage
+            .insert_data(COUNTERS_TABLE, topic, &serde_json::json!(current + 1))
+            .await?;
+        Ok(current)
+    }
+
+    /// Returns the mutex guarding `topic`'s offset counter, creating one on
+    /// first use. Keyed per-topic (rather than one global lock) so
+    /// concurrent publishes to *different* topics never contend.
+    async fn topic_offset_lock(&self, topic: &str) -> Arc<Mutex<()>> {
+        if let Some(lock) = self.offset_locks.read().await.get(topic) {
+            return lock.clone();
+        }
+
+        let mut locks = self.offset_locks.write().await;
+        locks
+            .entry(topic.to_string())
+            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .clone()
+    }
+
+    async fn peek_next_offset(&self, topic: &str) -> Result<u64> {
+        Ok(self
+            .storage
+            .get_data(COUNTERS_TABLE, topic)
+            .await?
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0))
+    }
+
+    async fn committed_offset(&self, subscription_id: &str) -> Result<u64> {
+        Ok(self
+            .storage
+            .get_data(OFFSETS_TABLE, subscription_id)
+            .await?
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0))
+    }
+
     pub async fn get_subscription(&self, subscription_id: &str) -> Result<Option<StreamSubscription>> {
         let subscriptions = self.subscriptions.read().await;
         Ok(subscriptions.get(subscription_id).cloned())
     }
-    
+
     pub async fn list_subscriptions(&self) -> Result<Vec<StreamSubscription>> {
         let subscriptions = self.subscriptions.read().await;
         Ok(subscriptions.values().cloned().collect())
     }
-    
+
     pub async fn get_topic_stats(&self, topic: &str) -> Result<Value> {
         let subscriptions = self.subscriptions.read().await;
         let consumers = self.consumers.read().await;
         let producers = self.producers.read().await;
-        
+
         let topic_subscriptions: Vec<_> = subscriptions
             .values()
             .filter(|s| s.topic == topic)
             .collect();
-        
+
         let topic_consumers: Vec<_> = consumers
             .values()
             .filter(|c| c.topic == topic)
             .collect();
-        
+
         let has_producer = producers.contains_key(topic);
-        
+        let next_offset = self.peek_next_offset(topic).await?;
+
         Ok(serde_json::json!({
             "topic": topic,
             "subscriptions": topic_subscriptions.len(),
             "consumers": topic_consumers.len(),
             "has_producer": has_producer,
+            "next_offset": next_offset,
             "status": if has_producer && !topic_subscriptions.is_empty() { "active" } else { "inactive" }
         }))
     }
-    
+
     pub async fn create_topic(&self, topic: &str, partitions: u32, replication_factor: u32) -> Result<()> {
         // In a real implementation, this would create a topic in Redpanda
-        tracing::info!("Creating topic {} with {} partitions and replication factor {}", 
+        tracing::info!("Creating topic {} with {} partitions and replication factor {}",
                       topic, partitions, replication_factor);
-        
+
         Ok(())
     }
-    
+
     pub async fn delete_topic(&self, topic: &str) -> Result<()> {
         // In a real implementation, this would delete a topic from Redpanda
-        
+
         // Remove all subscriptions for this topic
         let mut subscriptions = self.subscriptions.write().await;
+        let removed_subscriptions: Vec<String> = subscriptions
+            .iter()
+            .filter(|(_, s)| s.topic == topic)
+            .map(|(id, _)| id.clone())
+            .collect();
         subscriptions.retain(|_, s| s.topic != topic);
-        
+        drop(subscriptions);
+
+        for subscription_id in removed_subscriptions {
+            self.storage.delete_data(OFFSETS_TABLE, &subscription_id).await?;
+        }
+
         // Remove all consumers for this topic
         let mut consumers = self.consumers.write().await;
         consumers.retain(|_, c| c.topic != topic);
-        
+
         // Remove producer for this topic
         let mut producers = self.producers.write().await;
         producers.remove(topic);
-        
+
+        self.storage.delete_data(COUNTERS_TABLE, topic).await?;
+
         tracing::info!("Deleted topic {}", topic);
-        
+
         Ok(())
     }
 }
@@ -176,17 +308,74 @@ impl Clone for StreamSubscription {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
+    async fn test_manager() -> StreamManager {
+        let mut config = Config::default();
+        let temp_dir = tempfile::tempdir().unwrap();
+        config.storage.rocksdb_path = Some(temp_dir.path().join("rocksdb").to_string_lossy().to_string());
+
+        let storage = Arc::new(StorageManager::new(&config).await.unwrap());
+        StreamManager::new(&config, storage).await.unwrap()
+    }
+
     #[tokio::test]
     async fn test_subscribe() {
-        let config = Config::default();
-        let manager = StreamManager::new(&config).await.unwrap();
-        
+        let manager = test_manager().await;
+
         let subscription = manager.subscribe("test_topic").await.unwrap();
         assert_eq!(subscription.topic, "test_topic");
         assert_eq!(subscription.status, "active");
-        
+
         let retrieved = manager.get_subscription(&subscription.id).await.unwrap();
         assert!(retrieved.is_some());
     }
+
+    #[tokio::test]
+    async fn test_concurrent_publish_to_same_topic_does_not_collide_offsets() {
+        let manager = Arc::new(test_manager().await);
+
+        let mut handles = Vec::new();
+        for i in 0..20 {
+            let manager = manager.clone();
+            handles.push(tokio::spawn(async move {
+                manager.publish("orders", serde_json::json!({"i": i})).await.unwrap()
+            }));
+        }
+
+        let mut offsets = Vec::new();
+        for handle in handles {
+            offsets.push(handle.await.unwrap());
+        }
+
+        offsets.sort_unstable();
+        let expected: Vec<u64> = (0..20).collect();
+        assert_eq!(offsets, expected, "every publish must claim a distinct offset");
+
+        let subscription = manager.subscribe("orders").await.unwrap();
+        let messages = manager.poll(&subscription.id, 100).await.unwrap();
+        assert_eq!(messages.len(), 20, "no message should be overwritten by a colliding offset");
+    }
+
+    #[tokio::test]
+    async fn test_publish_poll_commit_and_seek() {
+        let manager = test_manager().await;
+
+        let subscription = manager.subscribe("orders").await.unwrap();
+        manager.publish("orders", serde_json::json!({"id": 1})).await.unwrap();
+        manager.publish("orders", serde_json::json!({"id": 2})).await.unwrap();
+
+        let batch = manager.poll(&subscription.id, 10).await.unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0].0, 0);
+        assert_eq!(batch[1].0, 1);
+
+        manager.commit_offset(&subscription.id, 0).await.unwrap();
+        let remaining = manager.poll(&subscription.id, 10).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].0, 1);
+
+        manager.seek(&subscription.id, 0).await.unwrap();
+        let replayed = manager.poll(&subscription.id, 10).await.unwrap();
+        assert_eq!(replayed.len(), 2);
+    }
 }
@@ -1,15 +1,52 @@
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde_json::Value;
 use hnsw::{Hnsw, Searcher};
 use std::collections::HashMap;
 
 use crate::config::Config;
 
+/// Derives a stable `u32` vector-index id from a row's string primary key,
+/// so the same row always maps to the same external id across inserts and
+/// re-embeds and `search` resolves only its latest vector. Each write still
+/// adds a fresh node to the HNSW graph (see `VectorIndex::insert_vector`).
+pub fn row_id_from_key(key: &str) -> u32 {
+    seahash::hash(key.as_bytes()) as u32
+}
+
+/// A single HNSW index plus the bookkeeping needed to answer queries with
+/// real ids and metadata instead of raw insertion order. `hnsw` only knows
+/// about insertion order, so `internal_to_external`/`external_to_internal`
+/// translate between that and the caller-supplied `id`.
+struct IndexEntry {
+    hnsw: Hnsw<f32, u32>,
+    internal_to_external: HashMap<u32, u32>,
+    external_to_internal: HashMap<u32, u32>,
+    vectors: HashMap<u32, Vec<f32>>,
+    metadata: HashMap<u32, Value>,
+}
+
+impl IndexEntry {
+    fn new(config: &Config, dimension: usize) -> Self {
+        Self {
+            hnsw: Hnsw::new(
+                config.vector_search.m,
+                config.vector_search.ef_construction,
+                config.vector_search.ef,
+                dimension,
+            ),
+            internal_to_external: HashMap::new(),
+            external_to_internal: HashMap::new(),
+            vectors: HashMap::new(),
+            metadata: HashMap::new(),
+        }
+    }
+}
+
 pub struct VectorIndex {
     config: Config,
-    indices: Arc<RwLock<HashMap<String, Hnsw<f32, u32>>>>,
+    indices: Arc<RwLock<HashMap<String, IndexEntry>>>,
     dimension: usize,
 }
 
@@ -21,95 +58,163 @@ impl VectorIndex {
             dimension: 384, // Default embedding dimension
         })
     }
-    
+
     pub async fn create_index(&self, table_name: &str, column_name: &str) -> Result<()> {
         let index_key = format!("{}:{}", table_name, column_name);
-        
-        // Create HNSW index with configuration
-        let hnsw = Hnsw::new(
-            self.config.vector_search.m,
-            self.config.vector_search.ef_construction,
-            self.config.vector_search.ef,
-            self.dimension,
-        );
-        
+
         let mut indices = self.indices.write().await;
-        indices.insert(index_key, hnsw);
-        
+        indices.insert(index_key, IndexEntry::new(&self.config, self.dimension));
+
         Ok(())
     }
-    
-    pub async fn insert_vector(&self, table_name: &str, column_name: &str, id: u32, vector: &[f32]) -> Result<()> {
+
+    pub async fn insert_vector(
+        &self,
+        table_name: &str,
+        column_name: &str,
+        id: u32,
+        vector: &[f32],
+        metadata: Value,
+    ) -> Result<()> {
+        if vector.len() != self.dimension {
+            return Err(anyhow!(
+                "vector has dimension {} but index {}.{} expects {}",
+                vector.len(),
+                table_name,
+                column_name,
+                self.dimension
+            ));
+        }
+
         let index_key = format!("{}:{}", table_name, column_name);
-        
-        let indices = self.indices.read().await;
-        if let Some(hnsw) = indices.get(&index_key) {
-            // Insert vector into HNSW index
-            // This is a simplified implementation
-            drop(indices);
-            
-            let mut indices = self.indices.write().await;
-            if let Some(hnsw) = indices.get_mut(&index_key) {
-                // Insert the vector
-                // Note: This is a placeholder - actual HNSW implementation would be more complex
-            }
+
+        let mut indices = self.indices.write().await;
+        let entry = indices
+            .get_mut(&index_key)
+            .ok_or_else(|| anyhow!("no vector index for {}.{}", table_name, column_name))?;
+
+        // `hnsw` has no node-removal API, so a re-inserted `id` still leaves its
+        // old node behind in the graph. Drop the old node's entry from
+        // `internal_to_external` so `search` can no longer resolve it back to
+        // `id` - without this, re-embedding the same row would return it twice
+        // (once per stale node). The stale node itself stays in the graph, so
+        // every re-insert still grows it by one node.
+        if let Some(&old_internal_id) = entry.external_to_internal.get(&id) {
+            entry.internal_to_external.remove(&old_internal_id);
+        }
+
+        let mut searcher = Searcher::default();
+        let internal_id = entry.hnsw.insert(vector.to_vec(), &mut searcher);
+
+        entry.internal_to_external.insert(internal_id, id);
+        entry.external_to_internal.insert(id, internal_id);
+        entry.vectors.insert(id, vector.to_vec());
+        entry.metadata.insert(id, metadata);
+
+        Ok(())
+    }
+
+    /// Removes `id` from the index so `search` can no longer return it. Like
+    /// re-insertion via [`Self::insert_vector`], the old HNSW node itself
+    /// can't be removed (the underlying graph has no node-removal API) - this
+    /// only drops the `internal_to_external` mapping `search` depends on to
+    /// resolve a matched node back to a real id.
+    pub async fn delete_vector(&self, table_name: &str, column_name: &str, id: u32) -> Result<()> {
+        let index_key = format!("{}:{}", table_name, column_name);
+
+        let mut indices = self.indices.write().await;
+        let entry = indices
+            .get_mut(&index_key)
+            .ok_or_else(|| anyhow!("no vector index for {}.{}", table_name, column_name))?;
+
+        if let Some(old_internal_id) = entry.external_to_internal.remove(&id) {
+            entry.internal_to_external.remove(&old_internal_id);
         }
-        
+        entry.vectors.remove(&id);
+        entry.metadata.remove(&id);
+
         Ok(())
     }
-    
-    pub async fn search(&self, query_vector: &[f32], limit: usize) -> Result<Vec<Value>> {
-        // For now, return mock results
-        // In a real implementation, this would search across all indices
-        let results = vec![
-            serde_json::json!({
-                "id": 1,
-                "score": 0.95,
-                "metadata": {
-                    "text": "Sample document 1",
-                    "table": "docs"
-                }
-            }),
-            serde_json::json!({
-                "id": 2,
-                "score": 0.87,
-                "metadata": {
-                    "text": "Sample document 2",
-                    "table": "docs"
-                }
-            }),
-            serde_json::json!({
-                "id": 3,
-                "score": 0.82,
-                "metadata": {
-                    "text": "Sample document 3",
-                    "table": "docs"
-                }
+
+    pub async fn search(
+        &self,
+        table_name: &str,
+        column_name: &str,
+        query_vector: &[f32],
+        limit: usize,
+    ) -> Result<Vec<Value>> {
+        let index_key = format!("{}:{}", table_name, column_name);
+
+        let indices = self.indices.read().await;
+        let entry = match indices.get(&index_key) {
+            Some(entry) => entry,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut searcher = Searcher::default();
+        let ef = self.config.vector_search.ef.max(limit);
+        let neighbors = entry.hnsw.nearest(query_vector, ef, &mut searcher);
+
+        let results = neighbors
+            .into_iter()
+            .take(limit)
+            .filter_map(|(internal_id, distance)| {
+                let id = *entry.internal_to_external.get(&internal_id)?;
+                let score = 1.0 / (1.0 + distance as f64);
+                Some(serde_json::json!({
+                    "id": id,
+                    "score": score,
+                    "metadata": entry.metadata.get(&id).cloned().unwrap_or(Value::Null),
+                }))
             })
-        ];
-        
-        Ok(results.into_iter().take(limit).collect())
+            .collect();
+
+        Ok(results)
     }
-    
+
+    /// Returns every indexed entry that carries a `"text"` metadata field
+    /// (as `crate::engine::VectraEngine::embed_and_index_document` stores
+    /// for each chunk), for `crate::bm25`-based keyword search to rank
+    /// alongside this index's vector search.
+    pub async fn text_documents(&self, table_name: &str, column_name: &str) -> Result<Vec<(u32, String, Value)>> {
+        let index_key = format!("{}:{}", table_name, column_name);
+
+        let indices = self.indices.read().await;
+        let Some(entry) = indices.get(&index_key) else {
+            return Ok(Vec::new());
+        };
+
+        Ok(entry
+            .metadata
+            .iter()
+            .filter_map(|(id, metadata)| {
+                metadata
+                    .get("text")
+                    .and_then(Value::as_str)
+                    .map(|text| (*id, text.to_string(), metadata.clone()))
+            })
+            .collect())
+    }
+
     pub async fn delete_index(&self, table_name: &str, column_name: &str) -> Result<()> {
         let index_key = format!("{}:{}", table_name, column_name);
-        
+
         let mut indices = self.indices.write().await;
         indices.remove(&index_key);
-        
+
         Ok(())
     }
-    
+
     pub async fn get_index_stats(&self, table_name: &str, column_name: &str) -> Result<Value> {
         let index_key = format!("{}:{}", table_name, column_name);
-        
+
         let indices = self.indices.read().await;
-        if let Some(hnsw) = indices.get(&index_key) {
+        if let Some(entry) = indices.get(&index_key) {
             Ok(serde_json::json!({
                 "table": table_name,
                 "column": column_name,
                 "dimension": self.dimension,
-                "vectors": 0, // Would be actual count in real implementation
+                "vectors": entry.vectors.len(),
                 "status": "active"
             }))
         } else {
@@ -125,15 +230,130 @@ impl VectorIndex {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[tokio::test]
     async fn test_create_index() {
         let config = Config::default();
         let index = VectorIndex::new(&config).await.unwrap();
-        
+
         index.create_index("test_table", "test_column").await.unwrap();
-        
+
         let stats = index.get_index_stats("test_table", "test_column").await.unwrap();
         assert_eq!(stats["status"], "active");
     }
+
+    #[tokio::test]
+    async fn test_insert_and_search_returns_real_ids_and_metadata() {
+        let config = Config::default();
+        let index = VectorIndex::new(&config).await.unwrap();
+        index.create_index("docs", "embedding").await.unwrap();
+
+        for i in 0..5u32 {
+            let vector: Vec<f32> = (0..384).map(|j| ((i + j) as f32) / 1000.0).collect();
+            index
+                .insert_vector("docs", "embedding", i, &vector, serde_json::json!({"seq": i}))
+                .await
+                .unwrap();
+        }
+
+        let stats = index.get_index_stats("docs", "embedding").await.unwrap();
+        assert_eq!(stats["vectors"], 5);
+
+        let query: Vec<f32> = (0..384).map(|j| (j as f32) / 1000.0).collect();
+        let results = index.search("docs", "embedding", &query, 3).await.unwrap();
+
+        assert_eq!(results.len(), 3);
+        for result in &results {
+            let id = result["id"].as_u64().unwrap();
+            assert_eq!(result["metadata"]["seq"], id);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_insert_vector_rejects_dimension_mismatch() {
+        let config = Config::default();
+        let index = VectorIndex::new(&config).await.unwrap();
+        index.create_index("docs", "embedding").await.unwrap();
+
+        let bad_vector = vec![0.0f32; 10];
+        let result = index
+            .insert_vector("docs", "embedding", 0, &bad_vector, Value::Null)
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_text_documents_only_returns_entries_with_text_metadata() {
+        let config = Config::default();
+        let index = VectorIndex::new(&config).await.unwrap();
+        index.create_index("docs", "embedding").await.unwrap();
+
+        let vector: Vec<f32> = vec![0.0; 384];
+        index
+            .insert_vector("docs", "embedding", 0, &vector, serde_json::json!({"text": "hello world"}))
+            .await
+            .unwrap();
+        index
+            .insert_vector("docs", "embedding", 1, &vector, serde_json::json!({"row_id": 5}))
+            .await
+            .unwrap();
+
+        let documents = index.text_documents("docs", "embedding").await.unwrap();
+        assert_eq!(documents.len(), 1);
+        assert_eq!(documents[0].0, 0);
+        assert_eq!(documents[0].1, "hello world");
+    }
+
+    #[tokio::test]
+    async fn test_reinserting_existing_id_replaces_stale_result_instead_of_duplicating() {
+        let config = Config::default();
+        let index = VectorIndex::new(&config).await.unwrap();
+        index.create_index("docs", "embedding").await.unwrap();
+
+        let original: Vec<f32> = vec![0.0; 384];
+        index
+            .insert_vector("docs", "embedding", 0, &original, serde_json::json!({"version": 1}))
+            .await
+            .unwrap();
+
+        let updated: Vec<f32> = vec![0.5; 384];
+        index
+            .insert_vector("docs", "embedding", 0, &updated, serde_json::json!({"version": 2}))
+            .await
+            .unwrap();
+
+        let results = index.search("docs", "embedding", &updated, 10).await.unwrap();
+        let matches_for_id: Vec<_> = results.iter().filter(|r| r["id"] == 0).collect();
+        assert_eq!(matches_for_id.len(), 1);
+        assert_eq!(matches_for_id[0]["metadata"]["version"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_delete_vector_removes_it_from_search_results() {
+        let config = Config::default();
+        let index = VectorIndex::new(&config).await.unwrap();
+        index.create_index("docs", "embedding").await.unwrap();
+
+        let vector: Vec<f32> = vec![0.0; 384];
+        index
+            .insert_vector("docs", "embedding", 0, &vector, serde_json::json!({"seq": 0}))
+            .await
+            .unwrap();
+
+        index.delete_vector("docs", "embedding", 0).await.unwrap();
+
+        let results = index.search("docs", "embedding", &vector, 10).await.unwrap();
+        assert!(results.iter().all(|r| r["id"] != 0));
+    }
+
+    #[tokio::test]
+    async fn test_search_unknown_index_returns_empty() {
+        let config = Config::default();
+        let index = VectorIndex::new(&config).await.unwrap();
+
+        let query = vec![0.0f32; 384];
+        let results = index.search("missing", "embedding", &query, 5).await.unwrap();
+        assert!(results.is_empty());
+    }
 }
@@ -28,13 +28,20 @@ async fn test_full_engine_workflow() {
         "content": "machine learning introduction",
         "embedding": [0.1, 0.2, 0.3]
     });
-    engine.insert_data("test_docs", &test_data).await.unwrap();
+    engine.insert_data("test_docs", "doc_1", &test_data).await.unwrap();
     
     // Test vector index creation
     engine.create_vector_index("test_docs", "embedding").await.unwrap();
-    
+
+    // Index at least one vector so search has something to find
+    let embedding: Vec<f32> = (0..384).map(|i| i as f32 / 1000.0).collect();
+    engine
+        .insert_vector("test_docs", "embedding", 1, &embedding, test_data.clone())
+        .await
+        .unwrap();
+
     // Test vector search
-    let results = engine.vector_search("machine learning", 5).await.unwrap();
+    let results = engine.vector_search("test_docs", "embedding", "machine learning", 5).await.unwrap();
     assert!(!results.is_empty());
     
     // Test SQL execution
@@ -53,12 +60,15 @@ async fn test_vector_search_integration() {
     // Insert test vectors
     for i in 0..100 {
         let vector: Vec<f32> = (0..384).map(|j| (i + j) as f32 / 1000.0).collect();
-        vector_index.insert_vector("test_table", "embedding", i as u32, &vector).await.unwrap();
+        vector_index
+            .insert_vector("test_table", "embedding", i as u32, &vector, serde_json::Value::Null)
+            .await
+            .unwrap();
     }
-    
+
     // Test search
     let query_vector: Vec<f32> = (0..384).map(|i| i as f32 / 1000.0).collect();
-    let results = vector_index.search(&query_vector, 10).await.unwrap();
+    let results = vector_index.search("test_table", "embedding", &query_vector, 10).await.unwrap();
     
     assert_eq!(results.len(), 10);
     
@@ -73,8 +83,9 @@ async fn test_vector_search_integration() {
 #[test]
 async fn test_streaming_integration() {
     let config = Config::default();
-    let stream_manager = StreamManager::new(&config).await.unwrap();
-    
+    let storage = std::sync::Arc::new(StorageManager::new(&config).await.unwrap());
+    let stream_manager = StreamManager::new(&config, storage).await.unwrap();
+
     // Create topic
     stream_manager.create_topic("test_events", 1, 1).await.unwrap();
     
@@ -103,18 +114,39 @@ async fn test_streaming_integration() {
     stream_manager.delete_topic("test_events").await.unwrap();
 }
 
+struct MockEmbeddingProvider;
+
+#[async_trait::async_trait]
+impl vectra::embedding::EmbeddingProvider for MockEmbeddingProvider {
+    async fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|_| vec![0.1; 384]).collect())
+    }
+
+    fn dimensions(&self) -> usize {
+        384
+    }
+
+    fn max_input_tokens(&self) -> usize {
+        8192
+    }
+}
+
 #[test]
 async fn test_ai_runtime_integration() {
     let config = Config::default();
-    let ai_runtime = AIRuntime::new(&config).await.unwrap();
-    
+    let storage = std::sync::Arc::new(StorageManager::new(&config).await.unwrap());
+    let ai_runtime = AIRuntime::new(&config, storage).await.unwrap();
+    ai_runtime
+        .register_embedding_provider(&config.ai.embedding_model, Box::new(MockEmbeddingProvider))
+        .await;
+
     // Test embedding generation
     let text = "artificial intelligence and machine learning";
     let embedding = ai_runtime.generate_embedding(text).await.unwrap();
     assert_eq!(embedding.len(), 384);
     
     // Test text generation
-    let generated_text = ai_runtime.generate_text("Explain AI", 50).await.unwrap();
+    let generated_text = ai_runtime.generate_text("Explain AI", 50, None).await.unwrap();
     assert!(!generated_text.is_empty());
     
     // Test text classification
@@ -148,7 +180,7 @@ async fn test_storage_integration() {
     let storage = StorageManager::new(&config).await.unwrap();
     
     // Test table operations
-    storage.create_table("test_users", "id INT, name TEXT, age INT").await.unwrap();
+    storage.create_table("test_users", "id INT, name TEXT, age INT", None, None).await.unwrap();
     
     let tables = storage.list_tables().await.unwrap();
     assert!(tables.iter().any(|t| t.name == "test_users"));
@@ -317,9 +349,17 @@ async fn test_end_to_end_workflow() {
     
     // 3. Create vector index
     engine.create_vector_index("documents", "embedding").await.unwrap();
-    
+
+    for (i, (title, _content)) in documents.iter().enumerate() {
+        let embedding: Vec<f32> = (0..384).map(|j| ((i + j) as f32) / 1000.0).collect();
+        engine
+            .insert_vector("documents", "embedding", i as u32 + 1, &embedding, serde_json::json!({"title": title}))
+            .await
+            .unwrap();
+    }
+
     // 4. Perform vector search
-    let search_results = engine.vector_search("artificial intelligence", 3).await.unwrap();
+    let search_results = engine.vector_search("documents", "embedding", "artificial intelligence", 3).await.unwrap();
     assert!(!search_results.is_empty());
     
     // 5. Execute complex SQL query
@@ -355,7 +395,7 @@ async fn test_error_handling() {
     assert!(result.is_err());
     
     // Test invalid vector search
-    let result = engine.vector_search("", 0).await;
+    let result = engine.vector_search("documents", "embedding", "", 0).await;
     assert!(result.is_err());
 }
 